@@ -62,6 +62,27 @@ fn submit_value(c: &mut Criterion) {
     });
 }
 
+fn submit_prepared(c: &mut Criterion) {
+    let mut group = c.benchmark_group("submit_prepared");
+    let values = vec![Value::Gauge(15.0), Value::Gauge(10.0), Value::Gauge(12.0)];
+    group.bench_function("submit_value", |b| {
+        b.iter(|| {
+            ValueListBuilder::new("my-plugin", "load")
+                .plugin_instance("cpu0")
+                .values(&values)
+                .submit()
+        })
+    });
+    group.bench_function("prepared", |b| {
+        let mut submitter = ValueListBuilder::new("my-plugin", "load")
+            .plugin_instance("cpu0")
+            .prepare()
+            .unwrap();
+        b.iter(|| submitter.submit(&values))
+    });
+    group.finish();
+}
+
 fn gen_nul_string(c: &mut Criterion) {
     // While not behaviorally the same, both of these functions
     // will detect a null for the use case of preparing a
@@ -74,10 +95,16 @@ fn gen_nul_string(c: &mut Criterion) {
         })
     });
     group.bench_function("memchr", |b| {
-        b.iter(|| memchr::memchr(0, &"Hello world"[..].as_bytes()))
+        b.iter(|| memchr::memchr(0, "Hello world"[..].as_bytes()))
     });
     group.finish();
 }
 
-criterion_group!(benches, convert_to_value_list, submit_value, gen_nul_string);
+criterion_group!(
+    benches,
+    convert_to_value_list,
+    submit_value,
+    submit_prepared,
+    gen_nul_string
+);
 criterion_main!(benches);
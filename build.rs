@@ -3,16 +3,25 @@ use std::path::PathBuf;
 
 enum CollectdVersion {
     Collectd57,
+    Collectd58,
 }
 
 fn main() {
     let collectd_version = detect_collectd_version();
     let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
     let version = match collectd_version.as_str() {
-        "5.12" | "5.11" | "5.10" | "5.9" | "5.8" | "5.7" => CollectdVersion::Collectd57,
+        "5.7" => CollectdVersion::Collectd57,
+        "5.12" | "5.11" | "5.10" | "5.9" | "5.8" => CollectdVersion::Collectd58,
         x => panic!("Unrecognized collectd version: {}", x),
     };
 
+    // Collectd 5.8 replaced the fixed size `hostname_g` array with a heap allocated string, so
+    // code that reads it needs to know which bindings it is compiled against
+    println!("cargo:rustc-check-cfg=cfg(collectd58)");
+    if let CollectdVersion::Collectd58 = version {
+        println!("cargo:rustc-cfg=collectd58");
+    }
+
     bindings(out_path.join("bindings.rs"), version);
 }
 
@@ -95,6 +104,7 @@ fn bindings(loc: PathBuf, version: CollectdVersion) {
     } else {
         let arg = match version {
             CollectdVersion::Collectd57 => "-DCOLLECTD_57",
+            CollectdVersion::Collectd58 => "-DCOLLECTD_58",
         };
 
         builder = builder.clang_arg("-DHAVE_CONFIG_H").clang_arg(arg);
@@ -107,9 +117,14 @@ fn bindings(loc: PathBuf, version: CollectdVersion) {
         .allowlist_type("meta_data_t")
         .allowlist_function("plugin_.*")
         .allowlist_function("uc_get_rate")
+        .allowlist_function("global_option_get")
         .allowlist_function("meta_data_.*")
+        .allowlist_var("hostname_g")
+        .allowlist_var("interval_g")
+        .allowlist_var("timeout_g")
         .allowlist_var("OCONFIG_TYPE_.*")
         .allowlist_var("LOG_.*")
+        .allowlist_var("NOTIF_.*")
         .allowlist_var("DS_TYPE_.*")
         .allowlist_var("DATA_MAX_NAME_LEN")
         .allowlist_var("MD_TYPE_.*")
@@ -131,8 +146,11 @@ fn bindings(loc: PathBuf, version: CollectdVersion) {
     fs::copy(PathBuf::from(path), loc).expect("File to copy");
 }
 
+/// Pregenerated bindings, refreshed by building with `bindgen` and `COLLECTD_OVERWRITE` set
+/// against the headers of the respective collectd version
 fn version_to_path(version: CollectdVersion) -> &'static str {
     match version {
         CollectdVersion::Collectd57 => "src/bindings-57.rs",
+        CollectdVersion::Collectd58 => "src/bindings-58.rs",
     }
 }
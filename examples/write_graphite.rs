@@ -72,7 +72,7 @@ impl PluginManager for GraphiteManager {
 
         // Deserialize the collectd configuration into our configuration struct
         let config: GraphiteConfig =
-            collectd_plugin::de::from_collectd(config.unwrap_or_else(Default::default))?;
+            collectd_plugin::de::from_collectd(config.unwrap_or_default())?;
 
         let config: Vec<(String, Box<dyn Plugin>)> = config
             .nodes
@@ -139,25 +139,27 @@ impl<W: Write + Send> Plugin for GraphitePlugin<W> {
     fn write_values(&self, list: ValueList<'_>) -> Result<(), Box<dyn error::Error>> {
         // We use a heap allocated string to construct data to send to graphite. Collectd doesn't
         // use the heap (preferring fixed size arrays). We could get the same behavior using the
-        // ArrayString type from the arrayvec crate.
+        // ArrayString type from the arrayvec crate. The value list's identifier gives us the
+        // host, plugin, and type that collectd would use to name these values.
+        let id = list.identifier();
         let mut line = String::new();
         if let Some(ref prefix) = self.prefix {
             line.push_str(prefix.as_str());
             line.push('.');
         }
-        line.push_str(graphitize(list.host).deref());
+        line.push_str(graphitize(&id.host).deref());
         line.push('.');
-        line.push_str(graphitize(list.plugin).deref());
+        line.push_str(graphitize(&id.plugin).deref());
 
-        if let Some(instance) = list.plugin_instance {
+        if let Some(ref instance) = id.plugin_instance {
             line.push('-');
             line.push_str(graphitize(instance).deref());
         }
 
         line.push('.');
-        line.push_str(graphitize(list.type_).deref());
+        line.push_str(graphitize(&id.type_).deref());
 
-        if let Some(type_instance) = list.type_instance {
+        if let Some(ref type_instance) = id.type_instance {
             line.push('-');
             line.push_str(graphitize(type_instance).deref());
         }
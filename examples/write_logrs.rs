@@ -2,8 +2,8 @@
 
 use chrono::Duration;
 use collectd_plugin::{
-    collectd_log, collectd_log_raw, collectd_plugin, CollectdLoggerBuilder, ConfigItem, LogLevel,
-    Plugin, PluginCapabilities, PluginManager, PluginRegistration, ValueList,
+    collectd_log, collectd_log_raw, collectd_plugin, CollectdLoggerBuilder, ConfigItem, Identifier,
+    LogLevel, Plugin, PluginCapabilities, PluginManager, PluginRegistration, ValueList,
 };
 use log::{info, LevelFilter};
 use serde::Deserialize;
@@ -46,7 +46,7 @@ impl PluginManager for LogWritePlugin {
         collectd_log(LogLevel::Info, &line);
         info!("rust logging configuration: {:?}", config);
         let plugin: LogWritePlugin =
-            collectd_plugin::de::from_collectd(config.unwrap_or_else(Default::default))?;
+            collectd_plugin::de::from_collectd(config.unwrap_or_default())?;
         Ok(PluginRegistration::Single(Box::new(plugin)))
     }
 }
@@ -92,7 +92,7 @@ impl Plugin for LogWritePlugin {
     fn flush(
         &self,
         timeout: Option<Duration>,
-        identifier: Option<&str>,
+        identifier: Option<&Identifier>,
     ) -> Result<(), Box<dyn error::Error>> {
         info!(
             "flushing: timeout: {}, identifier: {}",
@@ -0,0 +1,332 @@
+use super::{from_array, length, submit_array_res, Value, ValueType};
+use crate::bindings::{
+    data_set_t, data_source_t, plugin_get_ds, plugin_register_data_set, plugin_unregister_data_set,
+};
+use crate::errors::{DataSetError, SubmitError, UnregisterError};
+use std::ffi::CString;
+use std::slice;
+
+/// Describes a single value of a data set: its name, how the value should be interpreted, and
+/// the range of acceptable values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataSource {
+    /// Name of the data source. If a data set has a single data source, this is often just "value"
+    pub name: String,
+
+    /// How collectd interprets the value
+    pub value_type: ValueType,
+
+    /// Minimum acceptable value. `NaN` (the default) signifies no minimum
+    pub min: f64,
+
+    /// Maximum acceptable value. `NaN` (the default) signifies no maximum
+    pub max: f64,
+}
+
+impl DataSource {
+    /// Creates a data source that is unbounded, in the same way that types.db denotes unbounded
+    /// ranges with "U"
+    pub fn new<T: Into<String>>(name: T, value_type: ValueType) -> DataSource {
+        DataSource {
+            name: name.into(),
+            value_type,
+            min: f64::NAN,
+            max: f64::NAN,
+        }
+    }
+
+    /// Sets the minimum acceptable value
+    pub fn min(mut self, min: f64) -> DataSource {
+        self.min = min;
+        self
+    }
+
+    /// Sets the maximum acceptable value
+    pub fn max(mut self, max: f64) -> DataSource {
+        self.max = max;
+        self
+    }
+}
+
+/// A type, as found in types.db, that determines how many values are expected for the type and
+/// how they are to be interpreted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataSet {
+    /// Name of the type that value lists reference
+    pub name: String,
+
+    /// The data sources, in the order that values are expected to be submitted in
+    pub sources: Vec<DataSource>,
+}
+
+impl DataSet {
+    pub fn new<T: Into<String>>(name: T, sources: Vec<DataSource>) -> DataSet {
+        DataSet {
+            name: name.into(),
+            sources,
+        }
+    }
+
+    /// Looks up the data set that collectd has for the type (eg: from types.db)
+    pub fn lookup(name: &str) -> Result<DataSet, DataSetError> {
+        let s = CString::new(name).map_err(|_| DataSetError::UnknownType(name.to_string()))?;
+        let ptr = unsafe { plugin_get_ds(s.as_ptr()) };
+        match unsafe { ptr.as_ref() } {
+            Some(set) => DataSet::from(set),
+            None => Err(DataSetError::UnknownType(name.to_string())),
+        }
+    }
+
+    /// Converts a collectd data set into its Rust equivalent
+    pub fn from(set: &data_set_t) -> Result<DataSet, DataSetError> {
+        let name = from_array(&set.type_)
+            .map_err(|err| DataSetError::Utf8 {
+                type_: String::from_utf8_lossy(unsafe {
+                    slice::from_raw_parts(set.type_.as_ptr() as *const u8, set.type_.len())
+                })
+                .trim_end_matches('\0')
+                .to_string(),
+                err,
+            })?
+            .to_string();
+
+        let sources = if !set.ds.is_null() {
+            unsafe { slice::from_raw_parts(set.ds, length(set.ds_num)) }
+                .iter()
+                .map(|source| {
+                    let value_type = ValueType::try_from(source.type_ as u32).ok_or_else(|| {
+                        DataSetError::UnknownValueType {
+                            type_: name.clone(),
+                            value_type: source.type_,
+                        }
+                    })?;
+
+                    let source_name =
+                        from_array(&source.name).map_err(|err| DataSetError::Utf8 {
+                            type_: name.clone(),
+                            err,
+                        })?;
+
+                    Ok(DataSource {
+                        name: source_name.to_string(),
+                        value_type,
+                        min: source.min,
+                        max: source.max,
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        } else {
+            Vec::new()
+        };
+
+        Ok(DataSet { name, sources })
+    }
+
+    /// Checks that the values conform to the data set: there is a value for every data source,
+    /// each value is of the same kind as its data source, and each value is within the range of
+    /// its data source (NaN values are considered unknown and are always accepted).
+    pub fn validate(&self, values: &[Value]) -> Result<(), DataSetError> {
+        if values.len() != self.sources.len() {
+            return Err(DataSetError::Length {
+                type_: self.name.clone(),
+                expected: self.sources.iter().map(|x| x.value_type).collect(),
+                received: values.len(),
+            });
+        }
+
+        for (value, source) in values.iter().zip(self.sources.iter()) {
+            if value.value_type() != source.value_type {
+                return Err(DataSetError::Mismatch {
+                    type_: self.name.clone(),
+                    source: source.name.clone(),
+                    expected: source.value_type,
+                    value: *value,
+                });
+            }
+
+            let x = match *value {
+                Value::Counter(x) | Value::Absolute(x) => x as f64,
+                Value::Gauge(x) => x,
+                Value::Derive(x) => x as f64,
+            };
+
+            // Comparisons against NaN are always false, so unbounded ranges are never violated
+            if x < source.min || x > source.max {
+                return Err(DataSetError::OutOfRange {
+                    type_: self.name.clone(),
+                    source: source.name.clone(),
+                    value: *value,
+                    min: source.min,
+                    max: source.max,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Registers the data set with collectd, so that values can be submitted under it without
+    /// the type being defined in types.db. A data set with the same name is replaced.
+    pub fn register(&self) -> Result<(), SubmitError> {
+        let type_ = submit_array_res(&self.name, "data set name")?;
+        let mut ds = self
+            .sources
+            .iter()
+            .map(|source| {
+                Ok(data_source_t {
+                    name: submit_array_res(&source.name, "data source name")?,
+                    type_: source.value_type as i32,
+                    min: source.min,
+                    max: source.max,
+                })
+            })
+            .collect::<Result<Vec<_>, SubmitError>>()?;
+
+        // Collectd copies the data set, so it's fine that our copy is freed afterwards
+        let set = data_set_t {
+            type_,
+            ds_num: ds.len(),
+            ds: ds.as_mut_ptr(),
+        };
+
+        match unsafe { plugin_register_data_set(&set) } {
+            0 => Ok(()),
+            i => Err(SubmitError::Dispatch(i)),
+        }
+    }
+
+    /// Removes the data set from collectd
+    pub fn unregister(&self) -> Result<(), UnregisterError> {
+        let err = |code| UnregisterError {
+            name: self.name.clone(),
+            callback: "data set",
+            code,
+        };
+
+        // A name with a null could never have been registered, so it's reported like collectd
+        // reports an unknown data set
+        let s = CString::new(self.name.as_str()).map_err(|_| err(-1))?;
+        match unsafe { plugin_unregister_data_set(s.as_ptr()) } {
+            0 => Ok(()),
+            code => Err(err(code)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bindings::{ARR_LENGTH, DS_TYPE_GAUGE};
+    use std::os::raw::c_char;
+
+    #[test]
+    fn test_data_source_unbounded() {
+        let source = DataSource::new("value", ValueType::Gauge);
+        assert!(source.min.is_nan());
+        assert!(source.max.is_nan());
+
+        let source = source.min(0.0);
+        assert_eq!(source.min, 0.0);
+        assert!(source.max.is_nan());
+    }
+
+    #[test]
+    fn test_register_data_set() {
+        let set = DataSet::new(
+            "queue",
+            vec![
+                DataSource::new("depth", ValueType::Gauge).min(0.0),
+                DataSource::new("processed", ValueType::Derive).min(0.0),
+            ],
+        );
+
+        assert_eq!(set.register().unwrap(), ());
+    }
+
+    #[test]
+    fn test_data_set_from() {
+        let mut name: [c_char; ARR_LENGTH] = [0; ARR_LENGTH];
+        name[0] = b'h' as c_char;
+        name[1] = b'i' as c_char;
+
+        let mut sources = vec![data_source_t {
+            name,
+            type_: DS_TYPE_GAUGE as i32,
+            min: 0.0,
+            max: 100.0,
+        }];
+
+        let set = data_set_t {
+            type_: name,
+            ds_num: 1,
+            ds: sources.as_mut_ptr(),
+        };
+
+        assert_eq!(
+            DataSet::from(&set).unwrap(),
+            DataSet::new(
+                "hi",
+                vec![DataSource::new("hi", ValueType::Gauge).min(0.0).max(100.0)]
+            )
+        );
+    }
+
+    #[test]
+    fn test_lookup_unknown_data_set() {
+        match DataSet::lookup("load") {
+            Err(DataSetError::UnknownType(ref x)) => assert_eq!(x, "load"),
+            _ => panic!("expected an unknown type"),
+        }
+    }
+
+    #[test]
+    fn test_validate_data_set() {
+        let set = DataSet::new(
+            "load",
+            vec![
+                DataSource::new("shortterm", ValueType::Gauge).min(0.0),
+                DataSource::new("midterm", ValueType::Gauge).min(0.0),
+                DataSource::new("longterm", ValueType::Gauge)
+                    .min(0.0)
+                    .max(100.0),
+            ],
+        );
+
+        let values = [Value::Gauge(1.0), Value::Gauge(f64::NAN), Value::Gauge(2.0)];
+        assert!(set.validate(&values).is_ok());
+
+        let err = set.validate(&values[..1]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "type: load expects 3 values (GAUGE, GAUGE, GAUGE) but received 1"
+        );
+
+        let values = [Value::Gauge(1.0), Value::Derive(1), Value::Gauge(2.0)];
+        let err = set.validate(&values).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "type: load, data source: midterm expects a GAUGE but received Derive(1)"
+        );
+
+        let values = [Value::Gauge(1.0), Value::Gauge(1.0), Value::Gauge(200.0)];
+        let err = set.validate(&values).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "type: load, data source: longterm received 200 which is outside of [0, 100]"
+        );
+    }
+
+    #[test]
+    fn test_register_data_set_long_name() {
+        let set = DataSet::new("a".repeat(200), vec![]);
+        assert!(set.register().is_err());
+    }
+
+    #[test]
+    fn test_unregister_data_set_null() {
+        assert_eq!(DataSet::new("queue", vec![]).unregister().unwrap(), ());
+
+        let err = DataSet::new("que\0ue", vec![]).unregister().unwrap_err();
+        assert_eq!(err.callback, "data set");
+    }
+}
@@ -0,0 +1,88 @@
+use super::CdTime;
+use crate::bindings::{global_option_get, hostname_g, interval_g, plugin_get_interval, timeout_g};
+use chrono::Duration;
+use std::ffi::{CStr, CString};
+use std::path::PathBuf;
+
+/// The global settings of collectd and the context of the calling thread
+#[derive(Debug, Clone, PartialEq)]
+pub struct Globals {
+    /// The interval of the calling plugin. This is the read interval inside a read callback and
+    /// otherwise collectd's global interval.
+    pub interval: Duration,
+
+    /// Collectd's global interval
+    pub global_interval: Duration,
+
+    /// The hostname that collectd attributes values to by default
+    pub hostname: String,
+
+    /// The number of intervals a value can be missing before it is considered gone
+    pub timeout: i32,
+
+    /// The working directory of collectd
+    pub base_dir: Option<PathBuf>,
+}
+
+/// Returns the global settings of collectd. Since these are read from the configuration, they are
+/// only reliable once collectd has been initialized (eg: in `PluginManager::initialize` or any
+/// plugin callback).
+pub fn globals() -> Globals {
+    Globals {
+        interval: CdTime::from(unsafe { plugin_get_interval() }).into(),
+        global_interval: CdTime::from(unsafe { interval_g }).into(),
+        hostname: hostname(),
+        timeout: unsafe { timeout_g },
+        base_dir: global_option("BaseDir").map(PathBuf::from),
+    }
+}
+
+fn global_option(option: &str) -> Option<String> {
+    let option = CString::new(option).ok()?;
+    let ptr = unsafe { global_option_get(option.as_ptr()) };
+    if ptr.is_null() {
+        None
+    } else {
+        let value = unsafe { CStr::from_ptr(ptr) }.to_string_lossy();
+        Some(value.into_owned()).filter(|x| !x.is_empty())
+    }
+}
+
+/// The hostname as collectd resolved it on startup (from `Hostname` or `FQDNLookup`)
+#[cfg(not(collectd58))]
+pub(crate) fn hostname() -> String {
+    // Taking the address of a static became safe in Rust 1.82
+    #[allow(unused_unsafe)]
+    let ptr = unsafe { std::ptr::addr_of!(hostname_g) }.cast();
+    unsafe { CStr::from_ptr(ptr) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// The hostname as collectd resolved it on startup (from `Hostname` or `FQDNLookup`)
+#[cfg(collectd58)]
+pub(crate) fn hostname() -> String {
+    let ptr = unsafe { hostname_g };
+    if ptr.is_null() {
+        String::new()
+    } else {
+        unsafe { CStr::from_ptr(ptr) }
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_globals_defaults() {
+        let globals = globals();
+        assert_eq!(globals.interval, Duration::seconds(10));
+        assert_eq!(globals.global_interval, Duration::seconds(10));
+        assert_eq!(globals.timeout, 2);
+        assert_eq!(globals.base_dir, None);
+        assert_eq!(globals.hostname, "localhost");
+    }
+}
@@ -0,0 +1,203 @@
+use crate::errors::ReceiveError;
+use std::fmt;
+use std::str::FromStr;
+
+/// Identifies a value list in the format that collectd uses for flushing, caching, and the
+/// unixsock plugin: "host/plugin-plugin_instance/type-type_instance", where the instances are
+/// optional.
+///
+/// ```
+/// use collectd_plugin::Identifier;
+///
+/// let id: Identifier = "localhost/cpu-0/cpu-idle".parse().unwrap();
+/// assert_eq!(id.plugin, "cpu");
+/// assert_eq!(id.plugin_instance.as_deref(), Some("0"));
+/// assert_eq!(id.to_string(), "localhost/cpu-0/cpu-idle");
+/// assert!(id.matches("*/cpu-*/cpu-idle"));
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Identifier {
+    /// The hostname where the values were collected
+    pub host: String,
+
+    /// The plugin that submitted the values
+    pub plugin: String,
+
+    /// Distinguishes entities that yield metrics
+    pub plugin_instance: Option<String>,
+
+    /// This is the string found in types.db
+    pub type_: String,
+
+    /// Separates values of identical type which nonetheless belong to one another
+    pub type_instance: Option<String>,
+}
+
+impl Identifier {
+    pub fn new(
+        host: &str,
+        plugin: &str,
+        plugin_instance: Option<&str>,
+        type_: &str,
+        type_instance: Option<&str>,
+    ) -> Identifier {
+        Identifier {
+            host: String::from(host),
+            plugin: String::from(plugin),
+            plugin_instance: plugin_instance.map(String::from),
+            type_: String::from(type_),
+            type_instance: type_instance.map(String::from),
+        }
+    }
+
+    /// Matches the identifier against a glob pattern, where `*` matches any number of characters
+    /// and `?` matches a single character (eg: "*/cpu-*/cpu-idle").
+    pub fn matches(&self, pattern: &str) -> bool {
+        glob(pattern.as_bytes(), self.to_string().as_bytes())
+    }
+}
+
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.host, self.plugin)?;
+        if let Some(ref instance) = self.plugin_instance {
+            write!(f, "-{}", instance)?;
+        }
+
+        write!(f, "/{}", self.type_)?;
+        if let Some(ref instance) = self.type_instance {
+            write!(f, "-{}", instance)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Identifier {
+    type Err = ReceiveError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = |msg| ReceiveError::Identifier {
+            identifier: String::from(s),
+            msg,
+        };
+
+        let mut parts = s.split('/');
+        let host = parts.next().unwrap_or_default();
+        let plugin = parts.next().ok_or_else(|| err("missing plugin"))?;
+        let type_ = parts.next().ok_or_else(|| err("missing type"))?;
+        if parts.next().is_some() {
+            return Err(err("expected exactly two slashes"));
+        }
+
+        if host.is_empty() {
+            return Err(err("empty host"));
+        }
+
+        // Like collectd, the instance is everything after the first dash
+        let split = |x: &str| match x.split_once('-') {
+            Some((name, instance)) => (String::from(name), Some(String::from(instance))),
+            None => (String::from(x), None),
+        };
+
+        let (plugin, plugin_instance) = split(plugin);
+        if plugin.is_empty() {
+            return Err(err("empty plugin"));
+        }
+
+        let (type_, type_instance) = split(type_);
+        if type_.is_empty() {
+            return Err(err("empty type"));
+        }
+
+        Ok(Identifier {
+            host: String::from(host),
+            plugin,
+            plugin_instance,
+            type_,
+            type_instance,
+        })
+    }
+}
+
+fn glob(pattern: &[u8], text: &[u8]) -> bool {
+    // Iterative matching with backtracking to the last star, which avoids exponential blowup
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == b'?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((sp, st)) => {
+                    p = sp + 1;
+                    t = st + 1;
+                    star = Some((sp, st + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identifier_roundtrip() {
+        for s in &[
+            "localhost/cpu-0/cpu-idle",
+            "localhost/load/load",
+            "localhost/df-root/df_complex-used-reserved",
+        ] {
+            let id: Identifier = s.parse().unwrap();
+            assert_eq!(&id.to_string(), s);
+        }
+
+        let id: Identifier = "localhost/df-root/df_complex-used-reserved"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            id,
+            Identifier::new(
+                "localhost",
+                "df",
+                Some("root"),
+                "df_complex",
+                Some("used-reserved")
+            )
+        );
+    }
+
+    #[test]
+    fn test_identifier_malformed() {
+        let err = "localhost/cpu".parse::<Identifier>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "identifier: localhost/cpu is malformed: missing type"
+        );
+
+        assert!("localhost/cpu/cpu/idle".parse::<Identifier>().is_err());
+        assert!("/cpu/cpu".parse::<Identifier>().is_err());
+        assert!("localhost/-0/cpu".parse::<Identifier>().is_err());
+    }
+
+    #[test]
+    fn test_identifier_matches() {
+        let id: Identifier = "localhost/cpu-0/cpu-idle".parse().unwrap();
+        assert!(id.matches("localhost/cpu-0/cpu-idle"));
+        assert!(id.matches("*"));
+        assert!(id.matches("*/cpu-?/cpu-*"));
+        assert!(!id.matches("*/memory/*"));
+        assert!(!id.matches("localhost/cpu-0/cpu"));
+    }
+}
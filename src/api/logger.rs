@@ -8,7 +8,7 @@ use std::fmt::Write as FmtWrite;
 use std::io::{self, Write};
 
 /// A builder for configuring and installing a collectd logger.
-/// 
+///
 /// It is recommended to instantiate the logger in `PluginManager::plugins`.
 ///
 /// The use case of multiple rust plugins that instantiate a global logger is supported. Each
@@ -20,6 +20,12 @@ pub struct CollectdLoggerBuilder {
     format: Box<FormatFn>,
 }
 
+impl Default for CollectdLoggerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 type FormatFn = dyn Fn(&mut dyn Write, &Record<'_>) -> io::Result<()> + Sync + Send;
 
 impl CollectdLoggerBuilder {
@@ -1,14 +1,22 @@
 pub use self::cdtime::CdTime;
+pub use self::data_set::{DataSet, DataSource};
+pub use self::globals::{globals, Globals};
+pub use self::identifier::Identifier;
 pub use self::logger::{collectd_log, log_err, CollectdLogger, CollectdLoggerBuilder, LogLevel};
+pub use self::multivalue::MultiValueBuilder;
+pub use self::notification::{Notification, NotificationBuilder, NotificationSeverity};
+pub(crate) use self::oconfig::OwnedConfigItem;
 pub use self::oconfig::{ConfigItem, ConfigValue};
+pub use self::prepared::PreparedSubmitter;
 use crate::bindings::{
     data_set_t, meta_data_add_boolean, meta_data_add_double, meta_data_add_signed_int,
     meta_data_add_string, meta_data_add_unsigned_int, meta_data_create, meta_data_destroy,
     meta_data_get_boolean, meta_data_get_double, meta_data_get_signed_int, meta_data_get_string,
-    meta_data_get_unsigned_int, meta_data_t, meta_data_toc, meta_data_type, plugin_dispatch_values,
-    uc_get_rate, value_list_t, value_t, ARR_LENGTH, DS_TYPE_ABSOLUTE, DS_TYPE_COUNTER,
-    DS_TYPE_DERIVE, DS_TYPE_GAUGE, MD_TYPE_BOOLEAN, MD_TYPE_DOUBLE, MD_TYPE_SIGNED_INT,
-    MD_TYPE_STRING, MD_TYPE_UNSIGNED_INT,
+    meta_data_get_unsigned_int, meta_data_t, meta_data_toc, meta_data_type,
+    plugin_dispatch_missing, plugin_dispatch_values, plugin_get_interval, uc_get_rate,
+    value_list_t, value_t, ARR_LENGTH, DS_TYPE_ABSOLUTE, DS_TYPE_COUNTER, DS_TYPE_DERIVE,
+    DS_TYPE_GAUGE, MD_TYPE_BOOLEAN, MD_TYPE_DOUBLE, MD_TYPE_SIGNED_INT, MD_TYPE_STRING,
+    MD_TYPE_UNSIGNED_INT,
 };
 use crate::errors::{ArrayError, CacheRateError, ReceiveError, SubmitError};
 use chrono::prelude::*;
@@ -18,14 +26,20 @@ use std::borrow::Cow;
 use std::collections::HashMap;
 use std::ffi::{CStr, CString};
 use std::fmt;
-use std::os::raw::{c_char, c_void};
+use std::os::raw::{c_char, c_int, c_void};
 use std::ptr;
 use std::slice;
 use std::str::Utf8Error;
 
 mod cdtime;
+mod data_set;
+mod globals;
+mod identifier;
 mod logger;
+mod multivalue;
+mod notification;
 mod oconfig;
+mod prepared;
 
 /// The value of a metadata entry associated with a [ValueList].
 /// Metadata can be added using [ValueListBuilder::metadata] method.
@@ -38,16 +52,40 @@ pub enum MetaValue {
     Boolean(bool),
 }
 
+/// How collectd interprets the values of a data source. See [Value] for a description of each.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 #[repr(u32)]
-#[allow(dead_code)]
-enum ValueType {
+pub enum ValueType {
     Counter = DS_TYPE_COUNTER,
     Gauge = DS_TYPE_GAUGE,
     Derive = DS_TYPE_DERIVE,
     Absolute = DS_TYPE_ABSOLUTE,
 }
 
+impl ValueType {
+    /// Attempts to convert a u32 representing a collectd data source type into a Rust enum
+    pub fn try_from(s: u32) -> Option<ValueType> {
+        match s {
+            DS_TYPE_COUNTER => Some(ValueType::Counter),
+            DS_TYPE_GAUGE => Some(ValueType::Gauge),
+            DS_TYPE_DERIVE => Some(ValueType::Derive),
+            DS_TYPE_ABSOLUTE => Some(ValueType::Absolute),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ValueType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ValueType::Counter => write!(f, "COUNTER"),
+            ValueType::Gauge => write!(f, "GAUGE"),
+            ValueType::Derive => write!(f, "DERIVE"),
+            ValueType::Absolute => write!(f, "ABSOLUTE"),
+        }
+    }
+}
+
 /// The value that a plugin reports can be any one of the following types
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Value {
@@ -93,6 +131,22 @@ impl Value {
             false
         }
     }
+
+    /// Returns the type of data source that the value belongs to
+    ///
+    /// ```
+    /// # use collectd_plugin::{Value, ValueType};
+    /// assert_eq!(ValueType::Gauge, Value::Gauge(0.0).value_type());
+    /// assert_eq!(ValueType::Derive, Value::Derive(0).value_type());
+    /// ```
+    pub fn value_type(&self) -> ValueType {
+        match *self {
+            Value::Counter(_) => ValueType::Counter,
+            Value::Gauge(_) => ValueType::Gauge,
+            Value::Derive(_) => ValueType::Derive,
+            Value::Absolute(_) => ValueType::Absolute,
+        }
+    }
 }
 
 impl fmt::Display for Value {
@@ -190,23 +244,33 @@ impl<'a> ValueList<'a> {
             return Ok(Cow::Borrowed(&self.values));
         }
 
+        let rates = self.cache_rates().ok_or(CacheRateError)?;
+        let nv = rates
+            .iter()
+            .zip(self.values.iter())
+            .map(|(rate, report)| match report.value {
+                Value::Gauge(_) => *report,
+                _ => ValueReport {
+                    value: Value::Gauge(*rate),
+                    ..*report
+                },
+            })
+            .collect();
+        Ok(Cow::Owned(nv))
+    }
+
+    /// Looks up the rates of the values in collectd's cache. Gauges are their own rate.
+    fn cache_rates(&self) -> Option<Vec<f64>> {
         let ptr = unsafe { uc_get_rate(self.original_set, self.original_list) };
-        if !ptr.is_null() {
-            let nv = unsafe { slice::from_raw_parts(ptr, self.values.len()) }
-                .iter()
-                .zip(self.values.iter())
-                .map(|(rate, report)| match report.value {
-                    Value::Gauge(_) => *report,
-                    _ => ValueReport {
-                        value: Value::Gauge(*rate),
-                        ..*report
-                    },
-                })
-                .collect();
-            Ok(Cow::Owned(nv))
-        } else {
-            Err(CacheRateError)
+        if ptr.is_null() {
+            return None;
         }
+
+        let rates = unsafe { slice::from_raw_parts(ptr, self.values.len()) }.to_vec();
+
+        // The rates are allocated by collectd for us to own
+        unsafe { libc::free(ptr as *mut c_void) };
+        Some(rates)
     }
 
     pub fn from<'b>(
@@ -271,22 +335,179 @@ impl<'a> ValueList<'a> {
             original_set: set,
         })
     }
+
+    /// The identifier of the value list (eg: to route or filter values)
+    pub fn identifier(&self) -> Identifier {
+        Identifier::new(
+            self.host,
+            self.plugin,
+            self.plugin_instance,
+            self.type_,
+            self.type_instance,
+        )
+    }
+
+    /// Copies the value list so that it can be kept past the callback that received it (eg: to
+    /// buffer it in a `BufferedWriter` or hand it off to another thread). Since rates can only be calculated while
+    /// collectd's value list is alive, they are calculated beforehand.
+    pub fn into_owned(self) -> ValueListOwned {
+        let all_gauges = self
+            .values
+            .iter()
+            .all(|x| matches!(x.value, Value::Gauge(_)));
+
+        let rates = if all_gauges {
+            Some(
+                self.values
+                    .iter()
+                    .map(|x| match x.value {
+                        Value::Gauge(x) => x,
+                        _ => unreachable!(),
+                    })
+                    .collect(),
+            )
+        } else {
+            self.cache_rates()
+        };
+
+        ValueListOwned {
+            rates,
+            values: self
+                .values
+                .iter()
+                .map(|report| ValueReportOwned {
+                    name: String::from(report.name),
+                    value: report.value,
+                    min: report.min,
+                    max: report.max,
+                })
+                .collect(),
+            plugin: String::from(self.plugin),
+            plugin_instance: self.plugin_instance.map(String::from),
+            type_: String::from(self.type_),
+            type_instance: self.type_instance.map(String::from),
+            host: String::from(self.host),
+            time: self.time,
+            interval: self.interval,
+            meta: self.meta,
+        }
+    }
+}
+
+/// The owned equivalent of `ValueReport`
+#[derive(Debug, PartialEq, Clone)]
+pub struct ValueReportOwned {
+    /// Name of the metric. If values has a length of 1, this is often just "value"
+    pub name: String,
+
+    /// The value reported
+    pub value: Value,
+
+    /// Minimum value seen in an interval
+    pub min: f64,
+
+    /// Maximum value seen in an interval
+    pub max: f64,
+}
+
+/// The owned equivalent of `ValueList`, created with `ValueList::into_owned`. Unlike a
+/// `ValueList`, it doesn't reference collectd's memory, so it can be sent to other threads and
+/// outlive the callback.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ValueListOwned {
+    pub values: Vec<ValueReportOwned>,
+
+    /// The rate of each value, as calculated by collectd's cache when the list was converted.
+    /// Gauges are their own rate. `None` when collectd doesn't have the rates cached.
+    pub rates: Option<Vec<f64>>,
+
+    /// The plugin that submitted this value
+    pub plugin: String,
+
+    /// Distinguishes entities that yield metrics
+    pub plugin_instance: Option<String>,
+
+    /// This is the string found in types.db
+    pub type_: String,
+
+    /// Separates values of identical type which nonetheless belong to one another
+    pub type_instance: Option<String>,
+
+    /// The hostname where the values were collectd
+    pub host: String,
+
+    /// The timestamp at which the value was collected
+    pub time: DateTime<Utc>,
+
+    /// The interval in which new values are to be expected
+    pub interval: Duration,
+
+    /// Metadata associated to the reported values
+    pub meta: HashMap<String, MetaValue>,
+}
+
+impl ValueListOwned {
+    /// The owned equivalent of `ValueList::rates`: the values where all non-gauge values are
+    /// replaced with their rate
+    pub fn rate_values(&self) -> Result<Vec<ValueReportOwned>, CacheRateError> {
+        let rates = self.rates.as_ref().ok_or(CacheRateError)?;
+        Ok(rates
+            .iter()
+            .zip(self.values.iter())
+            .map(|(rate, report)| ValueReportOwned {
+                value: match report.value {
+                    Value::Gauge(x) => Value::Gauge(x),
+                    _ => Value::Gauge(*rate),
+                },
+                ..report.clone()
+            })
+            .collect())
+    }
+
+    /// The identifier of the value list (eg: to route or filter values)
+    pub fn identifier(&self) -> Identifier {
+        Identifier::new(
+            &self.host,
+            &self.plugin,
+            self.plugin_instance.as_deref(),
+            &self.type_,
+            self.type_instance.as_deref(),
+        )
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
 struct SubmitValueList<'a> {
-    values: &'a [Value],
-    plugin_instance: Option<&'a str>,
-    plugin: &'a str,
-    type_: &'a str,
-    type_instance: Option<&'a str>,
-    host: Option<&'a str>,
+    values: Cow<'a, [Value]>,
+    plugin_instance: Option<Cow<'a, str>>,
+    plugin: Cow<'a, str>,
+    type_: Cow<'a, str>,
+    type_instance: Option<Cow<'a, str>>,
+    host: Option<Cow<'a, str>>,
     time: Option<DateTime<Utc>>,
     interval: Option<Duration>,
-    meta: HashMap<&'a str, MetaValue>,
+    meta: HashMap<Cow<'a, str>, MetaValue>,
 }
 
 /// Creates a value list to report values to collectd.
+///
+/// The identifier fields and metadata keys can be borrowed, which doesn't allocate, or owned,
+/// which is convenient when they are formatted on the fly:
+///
+/// ```
+/// use collectd_plugin::{Value, ValueListBuilder};
+///
+/// # fn submit() -> Result<(), collectd_plugin::SubmitError> {
+/// for (core, idle) in [10.0, 20.0].iter().enumerate() {
+///     ValueListBuilder::new("cpu", "cpu")
+///         .plugin_instance(format!("cpu{}", core))
+///         .type_instance("idle")
+///         .owned_values(vec![Value::Gauge(*idle)])
+///         .submit()?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
 #[derive(Debug, PartialEq, Clone)]
 pub struct ValueListBuilder<'a> {
     list: SubmitValueList<'a>,
@@ -295,10 +516,13 @@ pub struct ValueListBuilder<'a> {
 impl<'a> ValueListBuilder<'a> {
     /// Primes a value list for submission. `plugin` will most likely be the name from the
     /// `PluginManager` and `type_` is the datatype found in types.db
-    pub fn new<T: Into<&'a str>, U: Into<&'a str>>(plugin: T, type_: U) -> ValueListBuilder<'a> {
+    pub fn new<T: Into<Cow<'a, str>>, U: Into<Cow<'a, str>>>(
+        plugin: T,
+        type_: U,
+    ) -> ValueListBuilder<'a> {
         ValueListBuilder {
             list: SubmitValueList {
-                values: &[],
+                values: Cow::Borrowed(&[]),
                 plugin_instance: None,
                 plugin: plugin.into(),
                 type_: type_.into(),
@@ -313,13 +537,23 @@ impl<'a> ValueListBuilder<'a> {
 
     /// A set of observed values that belong to the same plugin and type instance
     pub fn values(mut self, values: &'a [Value]) -> ValueListBuilder<'a> {
-        self.list.values = values;
+        self.list.values = Cow::Borrowed(values);
+        self
+    }
+
+    /// Same as `values`, but takes ownership of the values so that they don't need to outlive
+    /// the builder
+    pub fn owned_values(mut self, values: Vec<Value>) -> ValueListBuilder<'a> {
+        self.list.values = Cow::Owned(values);
         self
     }
 
     /// Distinguishes entities that yield metrics. Each core would be a different instance of the
     /// same plugin, as each core reports "idle", "user", "system" metrics.
-    pub fn plugin_instance<T: Into<&'a str>>(mut self, plugin_instance: T) -> ValueListBuilder<'a> {
+    pub fn plugin_instance<T: Into<Cow<'a, str>>>(
+        mut self,
+        plugin_instance: T,
+    ) -> ValueListBuilder<'a> {
         self.list.plugin_instance = Some(plugin_instance.into());
         self
     }
@@ -327,14 +561,17 @@ impl<'a> ValueListBuilder<'a> {
     /// The type instance is used to separate values of identical type which nonetheless belong to
     /// one another. For instance, even though "free", "used", and "total" all have types of
     /// "Memory" they are different type instances.
-    pub fn type_instance<T: Into<&'a str>>(mut self, type_instance: T) -> ValueListBuilder<'a> {
+    pub fn type_instance<T: Into<Cow<'a, str>>>(
+        mut self,
+        type_instance: T,
+    ) -> ValueListBuilder<'a> {
         self.list.type_instance = Some(type_instance.into());
         self
     }
 
     /// Override the machine's hostname that the observed values will be attributed to. Best to
     /// override when observing values from another machine
-    pub fn host<T: Into<&'a str>>(mut self, host: T) -> ValueListBuilder<'a> {
+    pub fn host<T: Into<Cow<'a, str>>>(mut self, host: T) -> ValueListBuilder<'a> {
         self.list.host = Some(host.into());
         self
     }
@@ -348,7 +585,9 @@ impl<'a> ValueListBuilder<'a> {
     }
 
     /// The interval in which new values are to be expected. This is typically handled at a global
-    /// or plugin level. Use at your own discretion.
+    /// or plugin level. Use at your own discretion. When left unset, collectd fills in the
+    /// interval of the read callback that is submitting the values, so plugins that override
+    /// `Plugin::read_interval` will have their values submitted with the matching interval.
     pub fn interval(mut self, interval: Duration) -> ValueListBuilder<'a> {
         self.list.interval = Some(interval);
         self
@@ -358,29 +597,100 @@ impl<'a> ValueListBuilder<'a> {
     ///
     /// Multiple entries can be added by calling this method. If the same key is used, only the last
     /// entry is kept.
-    pub fn metadata(mut self, key: &'a str, value: MetaValue) -> ValueListBuilder<'a> {
-        self.list.meta.insert(key, value);
+    pub fn metadata<K: Into<Cow<'a, str>>>(
+        mut self,
+        key: K,
+        value: MetaValue,
+    ) -> ValueListBuilder<'a> {
+        self.list.meta.insert(key.into(), value);
         self
     }
 
     /// Submits the observed values to collectd and returns errors if encountered
     pub fn submit(self) -> Result<(), SubmitError> {
+        match self.dispatch(|list| unsafe { plugin_dispatch_values(list) })? {
+            0 => Ok(()),
+            i => Err(SubmitError::Dispatch(i)),
+        }
+    }
+
+    /// Same as `submit`, but first looks up the type's data set and checks that the values
+    /// conform to it: the number of values, their kinds, and their ranges. Since collectd only
+    /// logs a generic error on nonconforming values, this is useful to track down a
+    /// misbehaving plugin at the cost of a data set lookup on every submission.
+    pub fn submit_validated(self) -> Result<(), SubmitError> {
+        DataSet::lookup(&self.list.type_)
+            .and_then(|set| set.validate(&self.list.values))
+            .map_err(SubmitError::DataSet)?;
+        self.submit()
+    }
+
+    /// Notifies plugins that registered a `missing` callback that the identified values are
+    /// missing (eg: the source is gone), so values are not required. Unlike `submit`, collectd
+    /// does not fill in the defaults of a missing value list, so the time defaults to now and the
+    /// interval defaults to the plugin's interval.
+    pub fn submit_missing(mut self) -> Result<(), SubmitError> {
+        self.list.time.get_or_insert_with(Utc::now);
+        self.list
+            .interval
+            .get_or_insert_with(|| CdTime::from(unsafe { plugin_get_interval() }).into());
+
+        match self.dispatch(|list| unsafe { plugin_dispatch_missing(list) })? {
+            0 => Ok(()),
+            i => Err(SubmitError::Dispatch(i)),
+        }
+    }
+
+    /// Prepares the value list for repeated submissions where only the values (and optionally
+    /// the time) change. The identifier, interval, and metadata are encoded once. See
+    /// `PreparedSubmitter`.
+    pub fn prepare(self) -> Result<PreparedSubmitter, SubmitError> {
+        let list = self.encode()?;
+        let values = self.list.values.iter().map(|&x| x.into()).collect();
+        Ok(PreparedSubmitter::new(list, values))
+    }
+
+    fn dispatch<F>(self, f: F) -> Result<c_int, SubmitError>
+    where
+        F: FnOnce(&value_list_t) -> c_int,
+    {
         let mut v: Vec<value_t> = self.list.values.iter().map(|&x| x.into()).collect();
+        let mut list = self.encode()?;
+        list.values = v.as_mut_ptr();
+        list.values_len = v.len();
+
+        let result = f(&list);
+
+        // Collectd clones the metadata of any value list that it holds onto, so we are still
+        // responsible for our copy
+        unsafe {
+            meta_data_destroy(list.meta);
+        }
+
+        Ok(result)
+    }
+
+    /// Encodes everything but the values into collectd's value list. The caller owns the
+    /// returned metadata.
+    fn encode(&self) -> Result<value_list_t, SubmitError> {
         let plugin_instance = self
             .list
             .plugin_instance
+            .as_deref()
             .map(|x| submit_array_res(x, "plugin_instance"))
             .unwrap_or_else(|| Ok([0 as c_char; ARR_LENGTH]))?;
 
         let type_instance = self
             .list
             .type_instance
+            .as_deref()
             .map(|x| submit_array_res(x, "type_instance"))
             .unwrap_or_else(|| Ok([0 as c_char; ARR_LENGTH]))?;
 
         let host = self
             .list
             .host
+            .as_deref()
             .map(|x| submit_array_res(x, "host"))
             .transpose()?;
 
@@ -392,17 +702,16 @@ impl<'a> ValueListBuilder<'a> {
         // submitted and would cause garbage to be read (and thus could have very much
         // unintended side effects)
         let host = host.unwrap_or([0 as c_char; ARR_LENGTH]);
-        let len = v.len();
 
-        let plugin = submit_array_res(self.list.plugin, "plugin")?;
+        let plugin = submit_array_res(&self.list.plugin, "plugin")?;
 
-        let type_ = submit_array_res(self.list.type_, "type")?;
+        let type_ = submit_array_res(&self.list.type_, "type")?;
 
         let meta = to_meta_data(&self.list.meta)?;
 
-        let list = value_list_t {
-            values: v.as_mut_ptr(),
-            values_len: len,
+        Ok(value_list_t {
+            values: ptr::null_mut(),
+            values_len: 0,
             plugin_instance,
             plugin,
             type_,
@@ -416,18 +725,14 @@ impl<'a> ValueListBuilder<'a> {
                 .unwrap_or(CdTime(0))
                 .into(),
             meta,
-        };
-
-        match unsafe { plugin_dispatch_values(&list) } {
-            0 => Ok(()),
-            i => Err(SubmitError::Dispatch(i)),
-        }
+        })
     }
 }
 
-fn to_meta_data<'a, 'b: 'a, T>(meta_hm: T) -> Result<*mut meta_data_t, SubmitError>
+fn to_meta_data<'a, K, T>(meta_hm: T) -> Result<*mut meta_data_t, SubmitError>
 where
-    T: IntoIterator<Item = (&'a &'b str, &'a MetaValue)>,
+    K: AsRef<str> + 'a,
+    T: IntoIterator<Item = (&'a K, &'a MetaValue)>,
 {
     let meta = unsafe { meta_data_create() };
     let conversion_result = to_meta_data_with_meta(meta_hm, meta);
@@ -442,15 +747,14 @@ where
     }
 }
 
-fn to_meta_data_with_meta<'a, 'b: 'a, T>(
-    meta_hm: T,
-    meta: *mut meta_data_t,
-) -> Result<(), SubmitError>
+fn to_meta_data_with_meta<'a, K, T>(meta_hm: T, meta: *mut meta_data_t) -> Result<(), SubmitError>
 where
-    T: IntoIterator<Item = (&'a &'b str, &'a MetaValue)>,
+    K: AsRef<str> + 'a,
+    T: IntoIterator<Item = (&'a K, &'a MetaValue)>,
 {
     for (key, value) in meta_hm.into_iter() {
-        let c_key = CString::new(*key).map_err(|e| SubmitError::Field {
+        let key = key.as_ref();
+        let c_key = CString::new(key).map_err(|e| SubmitError::Field {
             name: "meta key",
             err: ArrayError::NullPresent(e.nul_position(), key.to_string()),
         })?;
@@ -594,16 +898,20 @@ fn from_meta_data_with_toc(
     Ok(meta_hm)
 }
 
-fn submit_array_res(s: &str, name: &'static str) -> Result<[c_char; ARR_LENGTH], SubmitError> {
+fn submit_array_res<const N: usize>(
+    s: &str,
+    name: &'static str,
+) -> Result<[c_char; N], SubmitError> {
     to_array_res(s).map_err(|e| SubmitError::Field { name, err: e })
 }
 
 /// Collectd stores textual data in fixed sized arrays, so this function will convert a string
 /// slice into array compatible with collectd's text fields. Be aware that `ARR_LENGTH` is 64
-/// before collectd 5.7
-fn to_array_res(s: &str) -> Result<[c_char; ARR_LENGTH], ArrayError> {
+/// before collectd 5.7. Most fields are `ARR_LENGTH` long, but a few are not (eg: a
+/// notification's message).
+fn to_array_res<const N: usize>(s: &str) -> Result<[c_char; N], ArrayError> {
     // By checking if the length is greater than or *equal* to, we guarantee a trailing null
-    if s.len() >= ARR_LENGTH {
+    if s.len() >= N {
         return Err(ArrayError::TooLong(s.len()));
     }
 
@@ -616,9 +924,15 @@ fn to_array_res(s: &str) -> Result<[c_char; ARR_LENGTH], ArrayError> {
         return Err(ArrayError::NullPresent(ind, s.to_string()));
     }
 
-    let mut arr = [0; ARR_LENGTH];
-    arr[0..bytes.len()].copy_from_slice(bytes);
-    Ok(unsafe { ::std::mem::transmute::<[u8; ARR_LENGTH], [c_char; ARR_LENGTH]>(arr) })
+    let mut arr = [0 as c_char; N];
+    unsafe {
+        ptr::copy_nonoverlapping(
+            bytes.as_ptr() as *const c_char,
+            arr.as_mut_ptr(),
+            bytes.len(),
+        );
+    }
+    Ok(arr)
 }
 
 fn receive_array<'a>(
@@ -683,20 +997,20 @@ mod tests {
 
     #[test]
     fn test_to_array() {
-        let actual = to_array_res("Hi");
+        let actual = to_array_res::<ARR_LENGTH>("Hi");
         assert!(actual.is_ok());
         assert_eq!(&actual.unwrap()[..2], &[b'H' as c_char, b'i' as c_char]);
     }
 
     #[test]
     fn test_to_array_res_nul() {
-        let actual = to_array_res("hi\0");
+        let actual = to_array_res::<ARR_LENGTH>("hi\0");
         assert!(actual.is_err());
     }
 
     #[test]
     fn test_to_array_res_too_long() {
-        let actual = to_array_res(
+        let actual = to_array_res::<ARR_LENGTH>(
             "Hello check this out, I am a long string and there is no signs of stopping; well, maybe one day I will stop when I get too longggggggggggggggggggggggggggggggggggg",
         );
         assert!(actual.is_err());
@@ -711,6 +1025,36 @@ mod tests {
         assert_eq!(result.unwrap(), ());
     }
 
+    #[test]
+    fn test_submit_owned() {
+        let mut builders = Vec::new();
+        for core in 0..2 {
+            let builder = ValueListBuilder::new("cpu", String::from("cpu"))
+                .plugin_instance(format!("cpu{}", core))
+                .type_instance("idle")
+                .metadata(format!("core-{}", core), MetaValue::UnsignedInt(core))
+                .owned_values(vec![Value::Gauge(core as f64)]);
+            builders.push(builder);
+        }
+
+        for builder in builders {
+            assert_eq!(builder.submit().unwrap(), ());
+        }
+
+        let result = ValueListBuilder::new("cpu", "cpu")
+            .plugin_instance(String::from("cpu\0"))
+            .submit();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_submit_missing() {
+        let result = ValueListBuilder::new("my-plugin", "load")
+            .plugin_instance("eth0")
+            .submit_missing();
+        assert_eq!(result.unwrap(), ());
+    }
+
     #[test]
     fn test_recv_value_list_conversion() {
         let empty: [c_char; ARR_LENGTH] = [0; ARR_LENGTH];
@@ -774,5 +1118,37 @@ mod tests {
                 meta: HashMap::new(),
             }
         );
+
+        let owned = actual.into_owned();
+        assert_eq!(owned.rates, Some(vec![3.0]));
+        assert_eq!(owned.plugin_instance.as_deref(), Some("ho"));
+        assert_eq!(owned.values[0].name, "hi");
+        assert_eq!(owned.identifier().to_string(), "ho/hi-ho/ho");
+
+        // The owned list must be able to move to other threads
+        let handle = std::thread::spawn(move || owned.rate_values().unwrap());
+        assert_eq!(handle.join().unwrap()[0].value, Value::Gauge(3.0));
+    }
+
+    #[test]
+    fn test_owned_rates_missing_from_cache() {
+        let owned = ValueListOwned {
+            values: vec![ValueReportOwned {
+                name: String::from("value"),
+                value: Value::Counter(10),
+                min: 0.0,
+                max: f64::NAN,
+            }],
+            rates: None,
+            plugin: String::from("interface"),
+            plugin_instance: None,
+            type_: String::from("if_packets"),
+            type_instance: None,
+            host: String::from("localhost"),
+            time: Utc.with_ymd_and_hms(1970, 1, 1, 0, 0, 1).unwrap(),
+            interval: Duration::seconds(1),
+            meta: HashMap::new(),
+        };
+        assert!(owned.rate_values().is_err());
     }
 }
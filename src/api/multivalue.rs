@@ -0,0 +1,342 @@
+use super::{submit_array_res, MetaValue, Value, ValueListBuilder, ValueType};
+use crate::bindings::{plugin_dispatch_multivalue, value_t, ARR_LENGTH};
+use crate::errors::{DataSetError, SubmitError};
+use chrono::prelude::*;
+use chrono::Duration;
+use std::borrow::Cow;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+/// The most values that are submitted at once, as the number of arguments of a call to the
+/// variadic `plugin_dispatch_multivalue` is fixed at compile time
+const MULTIVALUE_MAX: usize = 64;
+
+/// Calls `plugin_dispatch_multivalue` with a (name, value) argument pair for each of the
+/// `MULTIVALUE_MAX` names and values, followed by the terminating null
+macro_rules! dispatch_multivalue {
+    ($list:expr, $percentage:expr, $store_type:expr, $names:expr, $values:expr) => {
+        dispatch_multivalue!(@call $list, $percentage, $store_type, $names, $values;
+            0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15
+            16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31
+            32 33 34 35 36 37 38 39 40 41 42 43 44 45 46 47
+            48 49 50 51 52 53 54 55 56 57 58 59 60 61 62 63
+        )
+    };
+
+    (@call $list:expr, $percentage:expr, $store_type:expr, $names:expr, $values:expr;
+        $($i:tt)*) => {
+        plugin_dispatch_multivalue(
+            $list,
+            $percentage,
+            $store_type,
+            $($names[$i], $values[$i],)*
+            ptr::null::<c_char>(),
+        )
+    };
+}
+
+/// The values as the arguments of the type that collectd reads them as
+fn arguments<T, F>(values: &[(Cow<'_, str>, Value)], f: F) -> [T; MULTIVALUE_MAX]
+where
+    T: Copy + Default,
+    F: Fn(Value) -> T,
+{
+    let mut args = [T::default(); MULTIVALUE_MAX];
+    for (arg, &(_, value)) in args.iter_mut().zip(values) {
+        *arg = f(value);
+    }
+    args
+}
+
+/// Submits several single value lists that only differ by their type instance (eg: the "used",
+/// "free", and "cached" memory of a machine) through collectd's `plugin_dispatch_multivalue`. The
+/// identifier, time, and metadata is encoded once and shared among all submissions.
+///
+/// ```
+/// use collectd_plugin::{MultiValueBuilder, Value};
+///
+/// # fn submit() -> Result<(), collectd_plugin::SubmitError> {
+/// MultiValueBuilder::new("memory", "memory")
+///     .value("used", Value::Gauge(600.0))
+///     .value("free", Value::Gauge(300.0))
+///     .value("cached", Value::Gauge(100.0))
+///     .submit()
+/// # }
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct MultiValueBuilder<'a> {
+    template: ValueListBuilder<'a>,
+    values: Vec<(Cow<'a, str>, Value)>,
+    store_percentage: bool,
+}
+
+impl<'a> MultiValueBuilder<'a> {
+    /// Primes the template value list for submission. `plugin` will most likely be the name from
+    /// the `PluginManager` and `type_` is the datatype found in types.db, which should consist of
+    /// a single data source.
+    pub fn new<T: Into<Cow<'a, str>>, U: Into<Cow<'a, str>>>(
+        plugin: T,
+        type_: U,
+    ) -> MultiValueBuilder<'a> {
+        MultiValueBuilder {
+            template: ValueListBuilder::new(plugin, type_),
+            values: Vec::new(),
+            store_percentage: false,
+        }
+    }
+
+    /// Adds an observed value that will be submitted under the given type instance
+    pub fn value<T: Into<Cow<'a, str>>>(
+        mut self,
+        type_instance: T,
+        value: Value,
+    ) -> MultiValueBuilder<'a> {
+        self.values.push((type_instance.into(), value));
+        self
+    }
+
+    /// Adds several observed values and the type instances they will be submitted under
+    pub fn values(mut self, values: &[(&'a str, Value)]) -> MultiValueBuilder<'a> {
+        self.values.extend(
+            values
+                .iter()
+                .map(|&(name, value)| (Cow::Borrowed(name), value)),
+        );
+        self
+    }
+
+    /// Instead of the observed values, submit each value's share of the total as a percentage.
+    /// Like collectd, the values are then submitted with the "percent" type and must all be
+    /// gauges.
+    pub fn store_percentage(mut self, store_percentage: bool) -> MultiValueBuilder<'a> {
+        self.store_percentage = store_percentage;
+        self
+    }
+
+    /// Distinguishes entities that yield metrics. See `ValueListBuilder::plugin_instance`
+    pub fn plugin_instance<T: Into<Cow<'a, str>>>(
+        mut self,
+        plugin_instance: T,
+    ) -> MultiValueBuilder<'a> {
+        self.template = self.template.plugin_instance(plugin_instance);
+        self
+    }
+
+    /// Override the machine's hostname that the observed values will be attributed to
+    pub fn host<T: Into<Cow<'a, str>>>(mut self, host: T) -> MultiValueBuilder<'a> {
+        self.template = self.template.host(host);
+        self
+    }
+
+    /// The timestamp at which the values were collected. See `ValueListBuilder::time`
+    pub fn time(mut self, dt: DateTime<Utc>) -> MultiValueBuilder<'a> {
+        self.template = self.template.time(dt);
+        self
+    }
+
+    /// The interval in which new values are to be expected. See `ValueListBuilder::interval`
+    pub fn interval(mut self, interval: Duration) -> MultiValueBuilder<'a> {
+        self.template = self.template.interval(interval);
+        self
+    }
+
+    /// Add a metadata entry that is shared among all the submitted values
+    pub fn metadata<K: Into<Cow<'a, str>>>(
+        mut self,
+        key: K,
+        value: MetaValue,
+    ) -> MultiValueBuilder<'a> {
+        self.template = self.template.metadata(key, value);
+        self
+    }
+
+    /// Submits the observed values to collectd in a single call, so that they share the same
+    /// timestamp. A failed submission does not prevent the remaining values from being
+    /// submitted, and the number of failures is returned. All values must be of the same kind and
+    /// at most 64 values can be submitted at once.
+    pub fn submit(self) -> Result<(), SubmitError> {
+        if self.values.len() > MULTIVALUE_MAX {
+            return Err(SubmitError::TooManyValues {
+                max: MULTIVALUE_MAX,
+                received: self.values.len(),
+            });
+        }
+
+        let store_type = match self.values.first() {
+            Some((_, value)) => value.value_type(),
+            None => return Ok(()),
+        };
+
+        let type_ = if self.store_percentage {
+            "percent"
+        } else {
+            &self.template.list.type_
+        };
+
+        let expected = if self.store_percentage {
+            ValueType::Gauge
+        } else {
+            store_type
+        };
+
+        let mut type_instances = Vec::with_capacity(self.values.len());
+        for (type_instance, value) in &self.values {
+            if value.value_type() != expected {
+                return Err(SubmitError::DataSet(DataSetError::Mismatch {
+                    type_: String::from(type_),
+                    source: type_instance.to_string(),
+                    expected,
+                    value: *value,
+                }));
+            }
+
+            type_instances.push(submit_array_res::<ARR_LENGTH>(
+                type_instance,
+                "type_instance",
+            )?);
+        }
+
+        // Unused names are null, which is also where collectd stops reading the arguments
+        let mut names = [ptr::null::<c_char>(); MULTIVALUE_MAX];
+        for (name, type_instance) in names.iter_mut().zip(&type_instances) {
+            *name = type_instance.as_ptr();
+        }
+
+        let values = &self.values;
+        let store_percentage = self.store_percentage;
+        let result = self.template.dispatch(|template| {
+            // Collectd overwrites the first value of its copy of the template for each submission
+            let mut placeholder = value_t { gauge: f64::NAN };
+            let mut list = *template;
+            list.values = &mut placeholder;
+            list.values_len = 1;
+
+            let store_type = store_type as c_int;
+            unsafe {
+                match expected {
+                    ValueType::Gauge => {
+                        let gauges = arguments(values, |x| match x {
+                            Value::Gauge(x) => x,
+                            _ => f64::NAN,
+                        });
+                        dispatch_multivalue!(&list, store_percentage, store_type, names, gauges)
+                    }
+                    ValueType::Derive => {
+                        let derives = arguments(values, |x| match x {
+                            Value::Derive(x) => x,
+                            _ => 0,
+                        });
+                        dispatch_multivalue!(&list, store_percentage, store_type, names, derives)
+                    }
+                    ValueType::Counter | ValueType::Absolute => {
+                        let counts = arguments(values, |x| match x {
+                            Value::Counter(x) | Value::Absolute(x) => x,
+                            _ => 0,
+                        });
+                        dispatch_multivalue!(&list, store_percentage, store_type, names, counts)
+                    }
+                }
+            }
+        })?;
+
+        match result {
+            0 => Ok(()),
+            i => Err(SubmitError::Dispatch(i)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bindings::overrides::MULTIVALUE_DISPATCHED;
+    use crate::bindings::{DS_TYPE_DERIVE, DS_TYPE_GAUGE};
+
+    #[test]
+    fn test_submit_multivalue() {
+        let result = MultiValueBuilder::new("memory", "memory")
+            .value("used", Value::Gauge(600.0))
+            .values(&[
+                ("free", Value::Gauge(300.0)),
+                ("cached", Value::Gauge(100.0)),
+            ])
+            .store_percentage(true)
+            .submit();
+        assert_eq!(result.unwrap(), ());
+
+        let dispatched = MULTIVALUE_DISPATCHED.with(|x| x.take());
+        assert_eq!(dispatched, Some((true, DS_TYPE_GAUGE as c_int, 1)));
+    }
+
+    #[test]
+    fn test_submit_multivalue_derive() {
+        let result = MultiValueBuilder::new("if_octets", "derive")
+            .value("rx", Value::Derive(600))
+            .value("tx", Value::Derive(300))
+            .submit();
+        assert_eq!(result.unwrap(), ());
+
+        let dispatched = MULTIVALUE_DISPATCHED.with(|x| x.take());
+        assert_eq!(dispatched, Some((false, DS_TYPE_DERIVE as c_int, 1)));
+    }
+
+    #[test]
+    fn test_submit_multivalue_mixed_types() {
+        let result = MultiValueBuilder::new("memory", "memory")
+            .value("used", Value::Gauge(600.0))
+            .value("free", Value::Derive(300))
+            .submit();
+
+        match result {
+            Err(SubmitError::DataSet(DataSetError::Mismatch { ref source, .. })) => {
+                assert_eq!(source, "free")
+            }
+            _ => panic!("expected a data set mismatch"),
+        }
+        assert_eq!(MULTIVALUE_DISPATCHED.with(|x| x.take()), None);
+    }
+
+    #[test]
+    fn test_submit_multivalue_too_many_values() {
+        let names: Vec<String> = (0..=MULTIVALUE_MAX).map(|x| x.to_string()).collect();
+        let mut builder = MultiValueBuilder::new("cpu", "percent");
+        for name in &names {
+            builder = builder.value(name.as_str(), Value::Gauge(1.0));
+        }
+
+        match builder.submit() {
+            Err(SubmitError::TooManyValues { max, received }) => {
+                assert_eq!((max, received), (MULTIVALUE_MAX, MULTIVALUE_MAX + 1))
+            }
+            _ => panic!("expected too many values"),
+        }
+    }
+
+    #[test]
+    fn test_submit_multivalue_percentage_of_derive() {
+        let result = MultiValueBuilder::new("memory", "memory")
+            .value("used", Value::Derive(600))
+            .store_percentage(true)
+            .submit();
+
+        match result {
+            Err(SubmitError::DataSet(DataSetError::Mismatch { ref type_, .. })) => {
+                assert_eq!(type_, "percent")
+            }
+            _ => panic!("expected a data set mismatch"),
+        }
+    }
+
+    #[test]
+    fn test_submit_multivalue_long_type_instance() {
+        let long = "a".repeat(ARR_LENGTH + 1);
+        let result = MultiValueBuilder::new("memory", "memory")
+            .value(long.as_str(), Value::Gauge(600.0))
+            .submit();
+
+        match result {
+            Err(SubmitError::Field { name, .. }) => assert_eq!(name, "type_instance"),
+            _ => panic!("expected a field error"),
+        }
+    }
+}
@@ -0,0 +1,459 @@
+use super::globals::hostname;
+use super::{submit_array_res, CdTime, MetaValue};
+use crate::bindings::{
+    notification_meta_t, notification_meta_type_e_NM_TYPE_BOOLEAN as NM_TYPE_BOOLEAN,
+    notification_meta_type_e_NM_TYPE_DOUBLE as NM_TYPE_DOUBLE,
+    notification_meta_type_e_NM_TYPE_SIGNED_INT as NM_TYPE_SIGNED_INT,
+    notification_meta_type_e_NM_TYPE_STRING as NM_TYPE_STRING,
+    notification_meta_type_e_NM_TYPE_UNSIGNED_INT as NM_TYPE_UNSIGNED_INT, notification_t,
+    plugin_dispatch_notification, plugin_notification_meta_add_boolean,
+    plugin_notification_meta_add_double, plugin_notification_meta_add_signed_int,
+    plugin_notification_meta_add_string, plugin_notification_meta_add_unsigned_int,
+    plugin_notification_meta_free, ARR_LENGTH, NOTIF_FAILURE, NOTIF_MAX_MSG_LEN, NOTIF_OKAY,
+    NOTIF_WARNING,
+};
+use crate::errors::{ArrayError, ReceiveError, SubmitError};
+use chrono::prelude::*;
+use memchr::memchr;
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::ptr;
+use std::slice;
+use std::str;
+
+/// The severities that collectd attaches to a notification
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[repr(u32)]
+pub enum NotificationSeverity {
+    Failure = NOTIF_FAILURE,
+    Warning = NOTIF_WARNING,
+    Okay = NOTIF_OKAY,
+}
+
+impl NotificationSeverity {
+    /// Attempts to convert a u32 representing a collectd notification severity into a Rust enum
+    pub fn try_from(s: u32) -> Option<NotificationSeverity> {
+        match s {
+            NOTIF_FAILURE => Some(NotificationSeverity::Failure),
+            NOTIF_WARNING => Some(NotificationSeverity::Warning),
+            NOTIF_OKAY => Some(NotificationSeverity::Okay),
+            _ => None,
+        }
+    }
+}
+
+/// An event (eg: a threshold being crossed) that collectd has dispatched to plugins that
+/// registered for notifications. Unlike a `ValueList`, every identifier field of a notification is
+/// optional, so empty fields are represented as `None`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Notification<'a> {
+    /// How severe the event is
+    pub severity: NotificationSeverity,
+
+    /// The timestamp at which the event occurred
+    pub time: DateTime<Utc>,
+
+    /// Human readable description of the event
+    pub message: &'a str,
+
+    /// The hostname the event is attributed to
+    pub host: &'a str,
+
+    /// The plugin that caused the event
+    pub plugin: Option<&'a str>,
+
+    /// The plugin instance that caused the event
+    pub plugin_instance: Option<&'a str>,
+
+    /// The type (from types.db) that the event concerns
+    pub type_: Option<&'a str>,
+
+    /// The type instance that the event concerns
+    pub type_instance: Option<&'a str>,
+
+    /// Metadata associated with the event
+    pub meta: HashMap<String, MetaValue>,
+}
+
+impl Notification<'_> {
+    pub fn from(n: &notification_t) -> Result<Notification<'_>, ReceiveError> {
+        let plugin = receive_field(&n.plugin, "", "plugin name")?;
+        let severity = NotificationSeverity::try_from(n.severity as u32).ok_or_else(|| {
+            ReceiveError::Severity {
+                plugin: plugin.to_string(),
+                severity: n.severity,
+            }
+        })?;
+
+        let message = receive_field(&n.message, plugin, "message")?;
+        let host = receive_field(&n.host, plugin, "host")?;
+        let plugin_instance = receive_field(&n.plugin_instance, plugin, "plugin_instance")?;
+        let type_ = receive_field(&n.type_, plugin, "type")?;
+        let type_instance = receive_field(&n.type_instance, plugin, "type_instance")?;
+        let meta = from_notification_meta(plugin, n.meta)?;
+
+        Ok(Notification {
+            severity,
+            time: CdTime::from(n.time).into(),
+            message,
+            host,
+            plugin: super::empty_to_none(plugin),
+            plugin_instance: super::empty_to_none(plugin_instance),
+            type_: super::empty_to_none(type_),
+            type_instance: super::empty_to_none(type_instance),
+            meta,
+        })
+    }
+}
+
+/// Creates a notification to dispatch to collectd.
+#[derive(Debug, PartialEq, Clone)]
+pub struct NotificationBuilder<'a> {
+    severity: NotificationSeverity,
+    message: &'a str,
+    plugin: &'a str,
+    plugin_instance: Option<&'a str>,
+    type_: Option<&'a str>,
+    type_instance: Option<&'a str>,
+    host: Option<&'a str>,
+    time: Option<DateTime<Utc>>,
+    meta: HashMap<&'a str, MetaValue>,
+}
+
+impl<'a> NotificationBuilder<'a> {
+    /// Primes a notification for dispatch. `plugin` will most likely be the name from the
+    /// `PluginManager`
+    pub fn new<T: Into<&'a str>>(plugin: T, severity: NotificationSeverity) -> Self {
+        NotificationBuilder {
+            severity,
+            message: "",
+            plugin: plugin.into(),
+            plugin_instance: None,
+            type_: None,
+            type_instance: None,
+            host: None,
+            time: None,
+            meta: HashMap::new(),
+        }
+    }
+
+    /// Human readable description of the event. Must be shorter than 256 bytes.
+    pub fn message<T: Into<&'a str>>(mut self, message: T) -> Self {
+        self.message = message.into();
+        self
+    }
+
+    /// The plugin instance that the event concerns
+    pub fn plugin_instance<T: Into<&'a str>>(mut self, plugin_instance: T) -> Self {
+        self.plugin_instance = Some(plugin_instance.into());
+        self
+    }
+
+    /// The type (from types.db) that the event concerns
+    pub fn type_<T: Into<&'a str>>(mut self, type_: T) -> Self {
+        self.type_ = Some(type_.into());
+        self
+    }
+
+    /// The type instance that the event concerns
+    pub fn type_instance<T: Into<&'a str>>(mut self, type_instance: T) -> Self {
+        self.type_instance = Some(type_instance.into());
+        self
+    }
+
+    /// The hostname that the event will be attributed to. Unlike values, collectd does not fill
+    /// in the hostname for notifications, so it defaults to the global hostname.
+    pub fn host<T: Into<&'a str>>(mut self, host: T) -> Self {
+        self.host = Some(host.into());
+        self
+    }
+
+    /// The timestamp at which the event occurred. Defaults to the time of `submit`.
+    pub fn time(mut self, dt: DateTime<Utc>) -> Self {
+        self.time = Some(dt);
+        self
+    }
+
+    /// Add a metadata entry.
+    ///
+    /// Multiple entries can be added by calling this method. If the same key is used, only the last
+    /// entry is kept.
+    pub fn metadata(mut self, key: &'a str, value: MetaValue) -> Self {
+        self.meta.insert(key, value);
+        self
+    }
+
+    /// Dispatches the notification to collectd and returns errors if encountered. Collectd
+    /// reports an error when no plugin has registered to receive notifications.
+    pub fn submit(self) -> Result<(), SubmitError> {
+        let empty = || Ok([0 as c_char; ARR_LENGTH]);
+        let message = submit_array_res::<{ NOTIF_MAX_MSG_LEN as usize }>(self.message, "message")?;
+        let plugin = submit_array_res(self.plugin, "plugin")?;
+        let plugin_instance = self
+            .plugin_instance
+            .map(|x| submit_array_res(x, "plugin_instance"))
+            .unwrap_or_else(empty)?;
+        let type_ = self
+            .type_
+            .map(|x| submit_array_res(x, "type"))
+            .unwrap_or_else(empty)?;
+        let type_instance = self
+            .type_instance
+            .map(|x| submit_array_res(x, "type_instance"))
+            .unwrap_or_else(empty)?;
+        let host = match self.host {
+            Some(host) => submit_array_res(host, "host")?,
+            None => submit_array_res(&hostname(), "host")?,
+        };
+
+        // Validate the keys before anything is allocated by collectd so that there is nothing to
+        // cleanup on failure
+        let meta = self
+            .meta
+            .iter()
+            .map(|(key, value)| {
+                CString::new(*key)
+                    .map(|k| (k, value))
+                    .map_err(|e| SubmitError::Field {
+                        name: "meta key",
+                        err: ArrayError::NullPresent(e.nul_position(), key.to_string()),
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let meta_strings = meta
+            .iter()
+            .filter_map(|(_, value)| match value {
+                MetaValue::String(s) => {
+                    Some(CString::new(s.as_str()).map_err(|e| SubmitError::Field {
+                        name: "meta value",
+                        err: ArrayError::NullPresent(e.nul_position(), s.to_string()),
+                    }))
+                }
+                _ => None,
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut notif = notification_t {
+            severity: self.severity as i32,
+            time: CdTime::from(self.time.unwrap_or_else(Utc::now)).into(),
+            message,
+            host,
+            plugin,
+            plugin_instance,
+            type_,
+            type_instance,
+            meta: ptr::null_mut(),
+        };
+
+        let mut strings = meta_strings.iter();
+        for (key, value) in &meta {
+            let n = &mut notif as *mut notification_t;
+            unsafe {
+                match value {
+                    MetaValue::String(_) => {
+                        let v = strings.next().expect("a string for every string entry");
+                        plugin_notification_meta_add_string(n, key.as_ptr(), v.as_ptr());
+                    }
+                    MetaValue::SignedInt(i) => {
+                        plugin_notification_meta_add_signed_int(n, key.as_ptr(), *i);
+                    }
+                    MetaValue::UnsignedInt(u) => {
+                        plugin_notification_meta_add_unsigned_int(n, key.as_ptr(), *u);
+                    }
+                    MetaValue::Double(d) => {
+                        plugin_notification_meta_add_double(n, key.as_ptr(), *d);
+                    }
+                    MetaValue::Boolean(b) => {
+                        plugin_notification_meta_add_boolean(n, key.as_ptr(), *b);
+                    }
+                }
+            }
+        }
+
+        let result = unsafe { plugin_dispatch_notification(&notif) };
+        unsafe {
+            plugin_notification_meta_free(notif.meta);
+        }
+
+        match result {
+            0 => Ok(()),
+            i => Err(SubmitError::Dispatch(i)),
+        }
+    }
+}
+
+/// Notifications contain fields of differing lengths (the message is longer than the
+/// identifiers), so the string is bounded by the array length or the first null, whichever is
+/// encountered first.
+fn receive_field<'a>(
+    s: &'a [c_char],
+    plugin: &str,
+    field: &'static str,
+) -> Result<&'a str, ReceiveError> {
+    let bytes = unsafe { slice::from_raw_parts(s.as_ptr() as *const u8, s.len()) };
+    let len = memchr(0, bytes).unwrap_or(bytes.len());
+    str::from_utf8(&bytes[..len]).map_err(|e| ReceiveError::Utf8 {
+        plugin: String::from(plugin),
+        field,
+        err: e,
+    })
+}
+
+fn from_notification_meta(
+    plugin: &str,
+    mut meta: *const notification_meta_t,
+) -> Result<HashMap<String, MetaValue>, ReceiveError> {
+    let mut meta_hm = HashMap::new();
+    while let Some(m) = unsafe { meta.as_ref() } {
+        let key = receive_field(&m.name, plugin, "metadata key")?.to_string();
+        let value = match m.type_ {
+            NM_TYPE_STRING => {
+                let s = unsafe { m.nm_value.nm_string };
+                if s.is_null() {
+                    MetaValue::String(String::new())
+                } else {
+                    let value =
+                        unsafe { CStr::from_ptr(s) }
+                            .to_str()
+                            .map_err(|e| ReceiveError::Utf8 {
+                                plugin: plugin.to_string(),
+                                field: "metadata value",
+                                err: e,
+                            })?;
+                    MetaValue::String(value.to_string())
+                }
+            }
+            NM_TYPE_SIGNED_INT => MetaValue::SignedInt(unsafe { m.nm_value.nm_signed_int }),
+            NM_TYPE_UNSIGNED_INT => MetaValue::UnsignedInt(unsafe { m.nm_value.nm_unsigned_int }),
+            NM_TYPE_DOUBLE => MetaValue::Double(unsafe { m.nm_value.nm_double }),
+            NM_TYPE_BOOLEAN => MetaValue::Boolean(unsafe { m.nm_value.nm_boolean }),
+            _ => {
+                return Err(ReceiveError::Metadata {
+                    plugin: plugin.to_string(),
+                    field: key,
+                    msg: "unknown metadata type",
+                });
+            }
+        };
+
+        meta_hm.insert(key, value);
+        meta = m.next;
+    }
+
+    Ok(meta_hm)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::cdtime::nanos_to_collectd;
+    use crate::bindings::{notification_meta_s__bindgen_ty_1, ARR_LENGTH};
+    use std::ffi::CString;
+    use std::ptr;
+
+    fn to_arr<const N: usize>(s: &str) -> [c_char; N] {
+        let mut arr = [0 as c_char; N];
+        for (i, b) in s.bytes().enumerate() {
+            arr[i] = b as c_char;
+        }
+        arr
+    }
+
+    #[test]
+    fn test_recv_notification_conversion() {
+        let region = CString::new("us-east").unwrap();
+        let mut second = notification_meta_t {
+            name: to_arr::<ARR_LENGTH>("region"),
+            type_: NM_TYPE_STRING,
+            nm_value: notification_meta_s__bindgen_ty_1 {
+                nm_string: region.as_ptr(),
+            },
+            next: ptr::null_mut(),
+        };
+
+        let mut first = notification_meta_t {
+            name: to_arr::<ARR_LENGTH>("retries"),
+            type_: NM_TYPE_UNSIGNED_INT,
+            nm_value: notification_meta_s__bindgen_ty_1 { nm_unsigned_int: 3 },
+            next: &mut second,
+        };
+
+        let n = notification_t {
+            severity: NOTIF_WARNING as i32,
+            time: nanos_to_collectd(1_000_000_000),
+            message: to_arr("disk is almost full"),
+            host: to_arr("localhost"),
+            plugin: to_arr("df"),
+            plugin_instance: to_arr("root"),
+            type_: to_arr("percent_bytes"),
+            type_instance: to_arr(""),
+            meta: &mut first,
+        };
+
+        let mut meta = HashMap::new();
+        meta.insert(String::from("retries"), MetaValue::UnsignedInt(3));
+        meta.insert(
+            String::from("region"),
+            MetaValue::String(String::from("us-east")),
+        );
+
+        let actual = Notification::from(&n).unwrap();
+        assert_eq!(
+            actual,
+            Notification {
+                severity: NotificationSeverity::Warning,
+                time: Utc.with_ymd_and_hms(1970, 1, 1, 0, 0, 1).unwrap(),
+                message: "disk is almost full",
+                host: "localhost",
+                plugin: Some("df"),
+                plugin_instance: Some("root"),
+                type_: Some("percent_bytes"),
+                type_instance: None,
+                meta,
+            }
+        );
+    }
+
+    #[test]
+    fn test_recv_notification_unknown_severity() {
+        let n = notification_t {
+            severity: 3,
+            time: 0,
+            message: to_arr("hello"),
+            host: to_arr("localhost"),
+            plugin: to_arr(""),
+            plugin_instance: to_arr(""),
+            type_: to_arr(""),
+            type_instance: to_arr(""),
+            meta: ptr::null_mut(),
+        };
+
+        assert!(Notification::from(&n).is_err());
+    }
+
+    #[test]
+    fn test_submit_notification() {
+        let result = NotificationBuilder::new("my-plugin", NotificationSeverity::Failure)
+            .message("something went wrong")
+            .plugin_instance("eth0")
+            .metadata("retries", MetaValue::UnsignedInt(3))
+            .metadata("region", MetaValue::String(String::from("us-east")))
+            .submit();
+        assert_eq!(result.unwrap(), ());
+    }
+
+    #[test]
+    fn test_submit_notification_message_too_long() {
+        let message = "a".repeat(NOTIF_MAX_MSG_LEN as usize);
+        let result = NotificationBuilder::new("my-plugin", NotificationSeverity::Okay)
+            .message(message.as_str())
+            .submit();
+
+        match result {
+            Err(SubmitError::Field { name, .. }) => assert_eq!(name, "message"),
+            _ => panic!("expected a field error"),
+        }
+    }
+}
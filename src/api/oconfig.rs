@@ -4,6 +4,7 @@ use crate::bindings::{
 };
 use crate::errors::ConfigError;
 use std::ffi::CStr;
+use std::fmt;
 use std::slice;
 
 /// A parsed value from the Collectd config
@@ -19,6 +20,17 @@ pub enum ConfigValue<'a> {
     String(&'a str),
 }
 
+/// Formats the value as it would be written in collectd.conf
+impl fmt::Display for ConfigValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ConfigValue::Number(x) => write!(f, "{}", x),
+            ConfigValue::Boolean(x) => write!(f, "{}", x),
+            ConfigValue::String(x) => write!(f, "{:?}", x),
+        }
+    }
+}
+
 /// Parsed key, values, children objects from the Collectd config.
 #[derive(Debug, PartialEq, Clone)]
 pub struct ConfigItem<'a> {
@@ -32,6 +44,60 @@ pub struct ConfigItem<'a> {
     pub children: Vec<ConfigItem<'a>>,
 }
 
+/// A copy of a `ConfigItem` that outlives the config callback, as collectd frees the config once
+/// it has been read.
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct OwnedConfigItem {
+    key: String,
+    values: Vec<OwnedConfigValue>,
+    children: Vec<OwnedConfigItem>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum OwnedConfigValue {
+    Number(f64),
+    Boolean(bool),
+    String(String),
+}
+
+impl OwnedConfigItem {
+    pub(crate) fn from(item: &ConfigItem<'_>) -> OwnedConfigItem {
+        let values = item
+            .values
+            .iter()
+            .map(|x| match *x {
+                ConfigValue::Number(x) => OwnedConfigValue::Number(x),
+                ConfigValue::Boolean(x) => OwnedConfigValue::Boolean(x),
+                ConfigValue::String(x) => OwnedConfigValue::String(String::from(x)),
+            })
+            .collect();
+
+        OwnedConfigItem {
+            key: String::from(item.key),
+            values,
+            children: item.children.iter().map(OwnedConfigItem::from).collect(),
+        }
+    }
+
+    pub(crate) fn as_item(&self) -> ConfigItem<'_> {
+        let values = self
+            .values
+            .iter()
+            .map(|x| match *x {
+                OwnedConfigValue::Number(x) => ConfigValue::Number(x),
+                OwnedConfigValue::Boolean(x) => ConfigValue::Boolean(x),
+                OwnedConfigValue::String(ref x) => ConfigValue::String(x.as_str()),
+            })
+            .collect();
+
+        ConfigItem {
+            key: self.key.as_str(),
+            values,
+            children: self.children.iter().map(OwnedConfigItem::as_item).collect(),
+        }
+    }
+}
+
 impl ConfigValue<'_> {
     /// # Safety
     ///
@@ -66,7 +132,7 @@ impl ConfigItem<'_> {
     /// # Safety
     ///
     /// Assumed that the pointer is non-null
-    pub unsafe fn from(item: &oconfig_item_t) -> Result<ConfigItem, ConfigError> {
+    pub unsafe fn from(item: &oconfig_item_t) -> Result<ConfigItem<'_>, ConfigError> {
         let key = CStr::from_ptr(item.key)
             .to_str()
             .map_err(ConfigError::StringDecode)?;
@@ -96,3 +162,24 @@ impl ConfigItem<'_> {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_owned_config_item() {
+        let item = ConfigItem {
+            key: "Node",
+            values: vec![ConfigValue::String("local")],
+            children: vec![ConfigItem {
+                key: "Port",
+                values: vec![ConfigValue::Number(2003.0), ConfigValue::Boolean(true)],
+                children: vec![],
+            }],
+        };
+
+        let owned = OwnedConfigItem::from(&item);
+        assert_eq!(owned.as_item(), item);
+    }
+}
@@ -0,0 +1,111 @@
+use super::{CdTime, Value};
+use crate::bindings::{cdtime_t, meta_data_destroy, plugin_dispatch_values, value_list_t, value_t};
+use crate::errors::SubmitError;
+use chrono::prelude::*;
+
+/// Submits values under an identifier that was encoded ahead of time, which avoids the cost of
+/// encoding the identifier and metadata on every submission. Useful for plugins that submit the
+/// same value lists on every read. Created with `ValueListBuilder::prepare`.
+///
+/// ```
+/// use collectd_plugin::{PreparedSubmitter, Value, ValueListBuilder};
+///
+/// # fn submit() -> Result<(), collectd_plugin::SubmitError> {
+/// let mut load = ValueListBuilder::new("myplugin", "load").prepare()?;
+/// for &(short, mid, long) in &[(1.0, 1.5, 2.0), (1.2, 1.6, 2.1)] {
+///     load.submit(&[Value::Gauge(short), Value::Gauge(mid), Value::Gauge(long)])?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct PreparedSubmitter {
+    list: value_list_t,
+    values: Vec<value_t>,
+}
+
+// The metadata is owned by the submitter and is only read by collectd, which clones it, during
+// a submission
+unsafe impl Send for PreparedSubmitter {}
+
+impl PreparedSubmitter {
+    pub(super) fn new(list: value_list_t, values: Vec<value_t>) -> Self {
+        PreparedSubmitter { list, values }
+    }
+
+    /// Submits the values to collectd. The time of the values is the time set on the builder,
+    /// else when collectd receives them.
+    pub fn submit(&mut self, values: &[Value]) -> Result<(), SubmitError> {
+        let time = self.list.time;
+        self.dispatch(time, values)
+    }
+
+    /// Submits the values to collectd that were collected at the given time
+    pub fn submit_at(&mut self, dt: DateTime<Utc>, values: &[Value]) -> Result<(), SubmitError> {
+        self.dispatch(CdTime::from(dt).into(), values)
+    }
+
+    fn dispatch(&mut self, time: cdtime_t, values: &[Value]) -> Result<(), SubmitError> {
+        // Reusing the buffer means that it is only allocated when the number of values grows
+        self.values.clear();
+        self.values.extend(values.iter().map(|&x| value_t::from(x)));
+
+        let mut list = self.list;
+        list.values = self.values.as_mut_ptr();
+        list.values_len = self.values.len();
+        list.time = time;
+
+        match unsafe { plugin_dispatch_values(&list) } {
+            0 => Ok(()),
+            i => Err(SubmitError::Dispatch(i)),
+        }
+    }
+}
+
+impl Drop for PreparedSubmitter {
+    fn drop(&mut self) {
+        unsafe {
+            meta_data_destroy(self.list.meta);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{MetaValue, ValueListBuilder};
+
+    #[test]
+    fn test_prepared_submit() {
+        let mut submitter = ValueListBuilder::new("my-plugin", "load")
+            .type_instance(format!("core{}", 0))
+            .metadata("key", MetaValue::Boolean(true))
+            .prepare()
+            .unwrap();
+
+        for x in 0..3 {
+            let values = [Value::Gauge(f64::from(x)); 3];
+            assert_eq!(submitter.submit(&values).unwrap(), ());
+        }
+
+        assert_eq!(
+            submitter
+                .submit_at(Utc::now(), &[Value::Gauge(1.0)])
+                .unwrap(),
+            ()
+        );
+    }
+
+    #[test]
+    fn test_prepare_invalid() {
+        let result = ValueListBuilder::new("my-plugin", "load")
+            .plugin_instance("a".repeat(200))
+            .prepare();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_prepared_send() {
+        fn assert_send<T: Send + 'static>() {}
+        assert_send::<PreparedSubmitter>();
+    }
+}
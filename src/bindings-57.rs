@@ -18,6 +18,10 @@ pub const LOG_WARNING: u32 = 4;
 pub const LOG_NOTICE: u32 = 5;
 pub const LOG_INFO: u32 = 6;
 pub const LOG_DEBUG: u32 = 7;
+pub const NOTIF_MAX_MSG_LEN: u32 = 256;
+pub const NOTIF_FAILURE: u32 = 1;
+pub const NOTIF_WARNING: u32 = 2;
+pub const NOTIF_OKAY: u32 = 4;
 #[repr(C)]
 #[derive(Copy, Clone)]
 pub struct oconfig_value_s {
@@ -1337,3 +1341,17 @@ extern "C" {
 extern "C" {
     pub fn uc_get_rate(ds: *const data_set_t, vl: *const value_list_t) -> *mut gauge_t;
 }
+extern "C" {
+    pub fn global_option_get(
+        option: *const ::std::os::raw::c_char,
+    ) -> *const ::std::os::raw::c_char;
+}
+extern "C" {
+    pub static mut hostname_g: [::std::os::raw::c_char; 0usize];
+}
+extern "C" {
+    pub static mut interval_g: cdtime_t;
+}
+extern "C" {
+    pub static mut timeout_g: ::std::os::raw::c_int;
+}
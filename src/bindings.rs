@@ -18,6 +18,258 @@ pub mod overrides {
         0
     }
 
+    #[no_mangle]
+    pub extern "C" fn plugin_register_data_set(ds: *const data_set_t) -> ::std::os::raw::c_int {
+        0
+    }
+
+    // Collectd's signature is variadic, but the stub never reads the arguments
+    #[no_mangle]
+    pub extern "C" fn plugin_log(
+        level: ::std::os::raw::c_int,
+        format: *const ::std::os::raw::c_char,
+    ) {
+    }
+
+    #[cfg(test)]
+    thread_local! {
+        /// The percentage flag, value type, and number of template values of the last call to
+        /// `plugin_dispatch_multivalue` on this thread
+        pub static MULTIVALUE_DISPATCHED: std::cell::Cell<Option<(bool, ::std::os::raw::c_int, usize)>> =
+            std::cell::Cell::new(None);
+    }
+
+    // Collectd's signature is variadic, but the stub never reads the names and values
+    #[no_mangle]
+    pub extern "C" fn plugin_dispatch_multivalue(
+        vl: *const value_list_t,
+        store_percentage: bool,
+        store_type: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int {
+        #[cfg(test)]
+        MULTIVALUE_DISPATCHED.with(|x| {
+            let values_len = unsafe { (*vl).values_len };
+            x.set(Some((store_percentage, store_type, values_len)))
+        });
+        0
+    }
+
+    #[no_mangle]
+    pub extern "C" fn plugin_unregister_data_set(
+        name: *const ::std::os::raw::c_char,
+    ) -> ::std::os::raw::c_int {
+        0
+    }
+
+    #[no_mangle]
+    pub extern "C" fn plugin_register_complex_read(
+        group: *const ::std::os::raw::c_char,
+        name: *const ::std::os::raw::c_char,
+        callback: plugin_read_cb,
+        interval: cdtime_t,
+        user_data: *const user_data_t,
+    ) -> ::std::os::raw::c_int {
+        0
+    }
+
+    #[no_mangle]
+    pub extern "C" fn plugin_register_write(
+        name: *const ::std::os::raw::c_char,
+        callback: plugin_write_cb,
+        user_data: *const user_data_t,
+    ) -> ::std::os::raw::c_int {
+        0
+    }
+
+    #[no_mangle]
+    pub extern "C" fn plugin_register_flush(
+        name: *const ::std::os::raw::c_char,
+        callback: plugin_flush_cb,
+        user_data: *const user_data_t,
+    ) -> ::std::os::raw::c_int {
+        0
+    }
+
+    #[no_mangle]
+    pub extern "C" fn plugin_register_missing(
+        name: *const ::std::os::raw::c_char,
+        callback: plugin_missing_cb,
+        user_data: *const user_data_t,
+    ) -> ::std::os::raw::c_int {
+        0
+    }
+
+    #[no_mangle]
+    pub extern "C" fn plugin_register_log(
+        name: *const ::std::os::raw::c_char,
+        callback: plugin_log_cb,
+        user_data: *const user_data_t,
+    ) -> ::std::os::raw::c_int {
+        0
+    }
+
+    #[no_mangle]
+    pub extern "C" fn plugin_register_notification(
+        name: *const ::std::os::raw::c_char,
+        callback: plugin_notification_cb,
+        user_data: *const user_data_t,
+    ) -> ::std::os::raw::c_int {
+        0
+    }
+
+    #[no_mangle]
+    pub extern "C" fn plugin_unregister_read(
+        name: *const ::std::os::raw::c_char,
+    ) -> ::std::os::raw::c_int {
+        0
+    }
+
+    #[no_mangle]
+    pub extern "C" fn plugin_unregister_write(
+        name: *const ::std::os::raw::c_char,
+    ) -> ::std::os::raw::c_int {
+        0
+    }
+
+    #[no_mangle]
+    pub extern "C" fn plugin_unregister_log(
+        name: *const ::std::os::raw::c_char,
+    ) -> ::std::os::raw::c_int {
+        0
+    }
+
+    #[no_mangle]
+    pub extern "C" fn plugin_unregister_flush(
+        name: *const ::std::os::raw::c_char,
+    ) -> ::std::os::raw::c_int {
+        0
+    }
+
+    #[no_mangle]
+    pub extern "C" fn plugin_unregister_notification(
+        name: *const ::std::os::raw::c_char,
+    ) -> ::std::os::raw::c_int {
+        0
+    }
+
+    #[no_mangle]
+    pub extern "C" fn plugin_unregister_missing(
+        name: *const ::std::os::raw::c_char,
+    ) -> ::std::os::raw::c_int {
+        0
+    }
+
+    #[no_mangle]
+    pub extern "C" fn uc_get_rate(ds: *const data_set_t, vl: *const value_list_t) -> *mut gauge_t {
+        std::ptr::null_mut()
+    }
+
+    #[no_mangle]
+    pub extern "C" fn plugin_get_ds(name: *const ::std::os::raw::c_char) -> *const data_set_t {
+        std::ptr::null()
+    }
+
+    #[no_mangle]
+    pub extern "C" fn plugin_dispatch_missing(vl: *const value_list_t) -> ::std::os::raw::c_int {
+        0
+    }
+
+    #[cfg(not(collectd58))]
+    #[no_mangle]
+    pub static hostname_g: [u8; 10] = *b"localhost\0";
+
+    #[cfg(collectd58)]
+    #[no_mangle]
+    pub static mut hostname_g: *mut ::std::os::raw::c_char =
+        b"localhost\0".as_ptr() as *mut ::std::os::raw::c_char;
+
+    // 10 seconds in collectd's 2^-30 second resolution
+    #[no_mangle]
+    pub static interval_g: cdtime_t = 10 << 30;
+
+    #[no_mangle]
+    pub static timeout_g: ::std::os::raw::c_int = 2;
+
+    #[no_mangle]
+    pub extern "C" fn global_option_get(
+        option: *const ::std::os::raw::c_char,
+    ) -> *const ::std::os::raw::c_char {
+        std::ptr::null()
+    }
+
+    #[no_mangle]
+    pub extern "C" fn plugin_get_ctx() -> plugin_ctx_t {
+        plugin_ctx_t {
+            interval: 0,
+            flush_interval: 0,
+            flush_timeout: 0,
+        }
+    }
+
+    #[no_mangle]
+    pub extern "C" fn plugin_set_ctx(ctx: plugin_ctx_t) -> plugin_ctx_t {
+        ctx
+    }
+
+    #[no_mangle]
+    pub extern "C" fn plugin_get_interval() -> cdtime_t {
+        10 << 30
+    }
+
+    #[no_mangle]
+    pub extern "C" fn plugin_dispatch_notification(
+        notif: *const notification_t,
+    ) -> ::std::os::raw::c_int {
+        0
+    }
+
+    #[no_mangle]
+    pub extern "C" fn plugin_notification_meta_add_string(
+        n: *mut notification_t,
+        name: *const ::std::os::raw::c_char,
+        value: *const ::std::os::raw::c_char,
+    ) -> ::std::os::raw::c_int {
+        0
+    }
+    #[no_mangle]
+    pub extern "C" fn plugin_notification_meta_add_signed_int(
+        n: *mut notification_t,
+        name: *const ::std::os::raw::c_char,
+        value: i64,
+    ) -> ::std::os::raw::c_int {
+        0
+    }
+    #[no_mangle]
+    pub extern "C" fn plugin_notification_meta_add_unsigned_int(
+        n: *mut notification_t,
+        name: *const ::std::os::raw::c_char,
+        value: u64,
+    ) -> ::std::os::raw::c_int {
+        0
+    }
+    #[no_mangle]
+    pub extern "C" fn plugin_notification_meta_add_double(
+        n: *mut notification_t,
+        name: *const ::std::os::raw::c_char,
+        value: f64,
+    ) -> ::std::os::raw::c_int {
+        0
+    }
+    #[no_mangle]
+    pub extern "C" fn plugin_notification_meta_add_boolean(
+        n: *mut notification_t,
+        name: *const ::std::os::raw::c_char,
+        value: bool,
+    ) -> ::std::os::raw::c_int {
+        0
+    }
+    #[no_mangle]
+    pub extern "C" fn plugin_notification_meta_free(
+        n: *mut notification_meta_t,
+    ) -> ::std::os::raw::c_int {
+        0
+    }
+
     #[no_mangle]
     pub extern "C" fn meta_data_create() -> *mut meta_data_t {
         std::ptr::null_mut()
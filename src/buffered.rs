@@ -0,0 +1,382 @@
+use crate::api::{Identifier, ValueList};
+use crate::plugins::{Plugin, PluginCapabilities};
+use chrono::prelude::*;
+use chrono::Duration;
+use std::collections::VecDeque;
+use std::error;
+use std::panic::{RefUnwindSafe, UnwindSafe};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, TryLockError};
+
+/// The destination of the batches of values buffered by a `BufferedWriter`
+pub trait BatchSink: Send + Sync + UnwindSafe + RefUnwindSafe {
+    /// What is buffered for each value list (eg: a line of a line based protocol)
+    type Item: Send;
+
+    /// Converts a value list into the item that is buffered. The value list borrows from
+    /// collectd, so anything that requires collectd (eg: `ValueList::rates`) must happen here.
+    fn convert(&self, list: ValueList<'_>) -> Result<Self::Item, Box<dyn error::Error>>;
+
+    /// Writes a batch of items, which are ordered from oldest to newest. The batch is dropped
+    /// if an error is returned.
+    fn write_batch(&self, batch: Vec<Self::Item>) -> Result<(), Box<dyn error::Error>>;
+}
+
+/// What a `BufferedWriter` does with values once its buffer is at capacity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DropPolicy {
+    /// Evict the oldest buffered value list to make room for the new one
+    #[default]
+    DropOldest,
+
+    /// Discard the new value list
+    DropNewest,
+}
+
+/// A buffered item along with what is needed to decide when it is written
+struct Entry<I> {
+    identifier: Identifier,
+    time: DateTime<Utc>,
+    item: I,
+}
+
+/// The entries to write along with the guard that marks them as being written
+type Batch<'a, I> = (MutexGuard<'a, ()>, Vec<Entry<I>>);
+
+/// A plugin that buffers the values written to it and hands them to a `BatchSink` in batches
+/// once `batch_size` value lists have been buffered or the oldest buffered value list is older
+/// than `max_age`. Collectd's flush requests (eg: from `collectdctl flush`) write the matching
+/// values immediately. Only one batch is written at a time, and values are buffered meanwhile.
+/// At most `capacity` value lists are buffered, so that a slow sink does not exhaust memory, and
+/// the `DropPolicy` decides which values are dropped.
+///
+/// ```
+/// use collectd_plugin::{BatchSink, BufferedWriter, PluginRegistration, ValueList};
+/// use std::error;
+///
+/// struct StdoutSink;
+///
+/// impl BatchSink for StdoutSink {
+///     type Item = String;
+///
+///     fn convert(&self, list: ValueList<'_>) -> Result<String, Box<dyn error::Error>> {
+///         let values: Vec<String> = list.values.iter().map(|x| x.value.to_string()).collect();
+///         Ok(format!("{}.{} {}", list.plugin, list.type_, values.join(":")))
+///     }
+///
+///     fn write_batch(&self, batch: Vec<String>) -> Result<(), Box<dyn error::Error>> {
+///         for line in batch {
+///             println!("{}", line);
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// fn registration() -> PluginRegistration {
+///     let plugin = BufferedWriter::new(StdoutSink).batch_size(500).capacity(10_000);
+///     PluginRegistration::Single(Box::new(plugin))
+/// }
+/// ```
+pub struct BufferedWriter<T: BatchSink> {
+    sink: T,
+    buffer: Mutex<VecDeque<Entry<T::Item>>>,
+    batch_size: usize,
+    max_age: Option<Duration>,
+    capacity: usize,
+    drop_policy: DropPolicy,
+    dropped: AtomicU64,
+
+    /// Held while a batch is taken out of the buffer and written to the sink
+    writing: Mutex<()>,
+}
+
+impl<T: BatchSink> BufferedWriter<T> {
+    /// Creates a writer that writes batches of 100 value lists and buffers at most 10000
+    pub fn new(sink: T) -> Self {
+        BufferedWriter {
+            sink,
+            buffer: Mutex::new(VecDeque::new()),
+            batch_size: 100,
+            max_age: None,
+            capacity: 10_000,
+            drop_policy: DropPolicy::default(),
+            dropped: AtomicU64::new(0),
+            writing: Mutex::new(()),
+        }
+    }
+
+    /// The number of buffered value lists that causes a batch to be written
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// The age (according to the time the values were collected) of the oldest buffered value
+    /// list that causes a batch to be written. Since the age is checked when values are written,
+    /// a batch may be written late when values are written infrequently.
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// The maximum number of buffered value lists. A batch is written once the buffer is at
+    /// capacity, even if that is less than `batch_size`.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Decides which values are dropped once the buffer is at capacity
+    pub fn drop_policy(mut self, drop_policy: DropPolicy) -> Self {
+        self.drop_policy = drop_policy;
+        self
+    }
+
+    /// The sink that batches are written to
+    pub fn sink(&self) -> &T {
+        &self.sink
+    }
+
+    /// The number of value lists that have been dropped because the buffer was at capacity
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<Entry<T::Item>>> {
+        self.buffer.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Waits until no other batch is being written
+    fn lock_writing(&self) -> MutexGuard<'_, ()> {
+        self.writing.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Buffers the entry and returns the batch that should be written, if any, along with the
+    /// guard that marks it as being written. The batch is written after releasing the buffer's
+    /// lock so that writes from other threads are not blocked on the sink.
+    fn buffer(&self, entry: Entry<T::Item>) -> Option<Batch<'_, T::Item>> {
+        let mut buffer = self.lock();
+        if buffer.len() >= self.capacity {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            match self.drop_policy {
+                DropPolicy::DropOldest => {
+                    buffer.pop_front();
+                    buffer.push_back(entry);
+                }
+                DropPolicy::DropNewest => {}
+            }
+        } else {
+            buffer.push_back(entry);
+        }
+
+        let expired = match (self.max_age, buffer.front()) {
+            (Some(age), Some(oldest)) => oldest.time <= Utc::now() - age,
+            _ => false,
+        };
+
+        let due = buffer.len() >= self.batch_size.min(self.capacity) || expired;
+        if !due {
+            return None;
+        }
+
+        let writing = match self.writing.try_lock() {
+            Ok(guard) => guard,
+            Err(TryLockError::Poisoned(e)) => e.into_inner(),
+            Err(TryLockError::WouldBlock) => return None,
+        };
+
+        Some((writing, buffer.drain(..).collect()))
+    }
+
+    fn write(&self, batch: Vec<Entry<T::Item>>) -> Result<(), Box<dyn error::Error>> {
+        if batch.is_empty() {
+            Ok(())
+        } else {
+            self.sink
+                .write_batch(batch.into_iter().map(|x| x.item).collect())
+        }
+    }
+}
+
+impl<T: BatchSink> Plugin for BufferedWriter<T> {
+    fn capabilities(&self) -> PluginCapabilities {
+        PluginCapabilities::WRITE | PluginCapabilities::FLUSH
+    }
+
+    fn write_values(&self, list: ValueList<'_>) -> Result<(), Box<dyn error::Error>> {
+        let entry = Entry {
+            identifier: list.identifier(),
+            time: list.time,
+            item: self.sink.convert(list)?,
+        };
+
+        match self.buffer(entry) {
+            Some((_writing, batch)) => self.write(batch),
+            None => Ok(()),
+        }
+    }
+
+    fn flush(
+        &self,
+        timeout: Option<Duration>,
+        identifier: Option<&Identifier>,
+    ) -> Result<(), Box<dyn error::Error>> {
+        let cutoff = timeout.map(|x| Utc::now() - x);
+        let _writing = self.lock_writing();
+        let batch = {
+            let mut buffer = self.lock();
+            let (batch, keep): (Vec<_>, Vec<_>) =
+                buffer.drain(..).partition(|entry: &Entry<T::Item>| {
+                    cutoff.map_or(true, |cutoff| entry.time <= cutoff)
+                        && identifier.map_or(true, |id| entry.identifier == *id)
+                });
+            *buffer = keep.into();
+            batch
+        };
+
+        self.write(batch)
+    }
+
+    fn shutdown(&self) -> Result<(), Box<dyn error::Error>> {
+        let _writing = self.lock_writing();
+        let batch = self.lock().drain(..).collect();
+        self.write(batch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[derive(Default)]
+    struct VecSink {
+        batches: Mutex<Vec<Vec<String>>>,
+    }
+
+    impl BatchSink for VecSink {
+        type Item = String;
+
+        fn convert(&self, list: ValueList<'_>) -> Result<String, Box<dyn error::Error>> {
+            Ok(list.identifier().to_string())
+        }
+
+        fn write_batch(&self, batch: Vec<String>) -> Result<(), Box<dyn error::Error>> {
+            self.batches.lock().unwrap().push(batch);
+            Ok(())
+        }
+    }
+
+    fn entry(type_instance: &str, time: DateTime<Utc>) -> Entry<String> {
+        let identifier = format!("localhost/memory/memory-{}", type_instance);
+        Entry {
+            identifier: identifier.parse().unwrap(),
+            time,
+            item: identifier,
+        }
+    }
+
+    fn batches(writer: &BufferedWriter<VecSink>) -> Vec<Vec<String>> {
+        writer.sink().batches.lock().unwrap().clone()
+    }
+
+    #[test]
+    fn test_batch_size() {
+        let writer = BufferedWriter::new(VecSink::default()).batch_size(2);
+        let now = Utc::now();
+        assert!(writer.buffer(entry("used", now)).is_none());
+        assert_eq!(writer.buffer(entry("free", now)).unwrap().1.len(), 2);
+        assert!(writer.lock().is_empty());
+    }
+
+    #[test]
+    fn test_capacity_below_batch_size() {
+        let writer = BufferedWriter::new(VecSink::default())
+            .batch_size(10)
+            .capacity(2);
+        let now = Utc::now();
+        assert!(writer.buffer(entry("used", now)).is_none());
+        assert_eq!(writer.buffer(entry("free", now)).unwrap().1.len(), 2);
+        assert_eq!(writer.dropped(), 0);
+    }
+
+    #[test]
+    fn test_max_age() {
+        let writer = BufferedWriter::new(VecSink::default()).max_age(Duration::seconds(30));
+        assert!(writer.buffer(entry("used", Utc::now())).is_none());
+
+        let old = Utc::now() - Duration::seconds(60);
+        let writer = BufferedWriter::new(VecSink::default()).max_age(Duration::seconds(30));
+        assert_eq!(writer.buffer(entry("used", old)).unwrap().1.len(), 1);
+    }
+
+    #[test]
+    fn test_flush_by_timeout_and_identifier() {
+        let writer = BufferedWriter::new(VecSink::default());
+        let now = Utc::now();
+        writer.buffer(entry("used", now - Duration::seconds(60)));
+        writer.buffer(entry("free", now - Duration::seconds(60)));
+        writer.buffer(entry("used", now));
+
+        writer
+            .flush(
+                Some(Duration::seconds(30)),
+                Some(&"localhost/memory/memory-used".parse().unwrap()),
+            )
+            .unwrap();
+        assert_eq!(batches(&writer), vec![vec!["localhost/memory/memory-used"]]);
+
+        writer.flush(None, None).unwrap();
+        assert_eq!(batches(&writer)[1].len(), 2);
+        assert!(writer.lock().is_empty());
+    }
+
+    #[test]
+    fn test_flush_waits_for_batch() {
+        let writer = Arc::new(BufferedWriter::new(VecSink::default()));
+        writer.buffer(entry("used", Utc::now()));
+
+        let writing = writer.lock_writing();
+        let flush = {
+            let writer = Arc::clone(&writer);
+            thread::spawn(move || writer.flush(None, None).unwrap())
+        };
+
+        thread::sleep(std::time::Duration::from_millis(50));
+        assert!(batches(&writer).is_empty());
+        assert_eq!(writer.lock().len(), 1);
+
+        drop(writing);
+        flush.join().unwrap();
+        assert_eq!(batches(&writer), vec![vec!["localhost/memory/memory-used"]]);
+    }
+
+    #[test]
+    fn test_drop_policy() {
+        let now = Utc::now();
+        for &(policy, expected) in &[
+            (DropPolicy::DropOldest, ["free", "cached"]),
+            (DropPolicy::DropNewest, ["used", "free"]),
+        ] {
+            let writer = BufferedWriter::new(VecSink::default())
+                .capacity(2)
+                .drop_policy(policy);
+
+            // The buffer fills up while another batch is being written to a slow sink
+            let writing = writer.lock_writing();
+            assert!(writer.buffer(entry("used", now)).is_none());
+            assert!(writer.buffer(entry("free", now)).is_none());
+            assert!(writer.buffer(entry("cached", now)).is_none());
+            assert_eq!(writer.dropped(), 1);
+            drop(writing);
+
+            writer.shutdown().unwrap();
+            let expected: Vec<_> = expected
+                .iter()
+                .map(|x| format!("localhost/memory/memory-{}", x))
+                .collect();
+            assert_eq!(batches(&writer), vec![expected]);
+        }
+    }
+}
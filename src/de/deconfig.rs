@@ -1,5 +1,6 @@
 use crate::api::{ConfigItem, ConfigValue};
 use std::collections::HashMap;
+use std::fmt;
 
 /// This looks just like `ConfigValue` except it add in the `Object` association. While collectd
 /// differentiate between values and children, for simplicity, we don't. It's kinda like JSON this
@@ -10,25 +11,60 @@ pub enum DeConfig<'a> {
     Boolean(bool),
     String(&'a str),
     Object(Vec<(&'a str, Vec<DeConfig<'a>>)>),
+
+    /// A block with values in its opening tag (eg: `<Database "prod">`), which are its labels
+    Labeled(Vec<DeConfig<'a>>, Vec<(&'a str, Vec<DeConfig<'a>>)>),
+}
+
+impl<'a> DeConfig<'a> {
+    /// The children of a block, whether it is labeled or not
+    pub fn children(&self) -> Option<&Vec<(&'a str, Vec<DeConfig<'a>>)>> {
+        match *self {
+            DeConfig::Object(ref children) | DeConfig::Labeled(_, ref children) => Some(children),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DeConfig<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            DeConfig::Number(x) => ConfigValue::Number(x).fmt(f),
+            DeConfig::Boolean(x) => ConfigValue::Boolean(x).fmt(f),
+            DeConfig::String(x) => ConfigValue::String(x).fmt(f),
+            DeConfig::Object(_) => write!(f, "<block>"),
+            DeConfig::Labeled(ref labels, _) => {
+                write!(f, "<block")?;
+                for label in labels {
+                    write!(f, " {}", label)?;
+                }
+                write!(f, ">")
+            }
+        }
+    }
 }
 
 /// Since a collectd config can (and often) contains multiple keys, we aggregate all instances of
 /// the same key under a single key. Serde likes it this way. Won't run into duplicate key errors.
+/// Blocks that have both values and children keep their values as labels, so that several
+/// labeled blocks don't blend together.
 pub fn from_config<'a>(s: &'a [ConfigItem<'a>]) -> Vec<(&'a str, Vec<DeConfig<'a>>)> {
     let mut props: HashMap<&'a str, Vec<DeConfig<'a>>> = HashMap::new();
     for item in s {
-        if !item.values.is_empty() {
-            props
+        match (item.values.is_empty(), item.children.is_empty()) {
+            (true, true) => {}
+            (false, true) => props
                 .entry(item.key)
                 .or_default()
-                .extend(item.values.iter().map(value_to_config));
-        }
-
-        if !item.children.is_empty() {
-            props
+                .extend(item.values.iter().map(value_to_config)),
+            (true, false) => props
                 .entry(item.key)
                 .or_default()
-                .push(de_config_item(&item.children[..]));
+                .push(de_config_item(&item.children[..])),
+            (false, false) => props.entry(item.key).or_default().push(DeConfig::Labeled(
+                item.values.iter().map(value_to_config).collect(),
+                from_config(&item.children[..]),
+            )),
         }
     }
 
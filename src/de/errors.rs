@@ -11,20 +11,63 @@ pub enum DeError {
     ExpectChar(String),
     ExpectBoolean,
     ExpectNumber,
+    NumberOutOfRange { target: &'static str, number: f64 },
     ExpectStruct,
     ExpectObject,
     DataTypeNotSupported,
+    ExpectVariant,
+    ExpectLabel,
+    UnexpectedValues(String),
 }
 
 // Since the failure crate can't automatically implement serde::de::Error (see issue
 // <https://github.com/withoutboats/failure/issues/108>) we define a thin wrapper around our actual
-// error type.
+// error type, which also records where in the config the error occurred.
 #[derive(Debug)]
-pub struct Error(pub DeError);
+pub struct Error {
+    kind: DeError,
+    path: String,
+    value: Option<String>,
+}
+
+impl Error {
+    /// The reason deserialization failed
+    pub fn kind(&self) -> &DeError {
+        &self.kind
+    }
+
+    /// The path to the config key that failed to deserialize, where nested blocks are separated
+    /// by a period and the index of a repeated key is in brackets (eg: `Node[2].Address`). Empty
+    /// when the error is not attributed to a key.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The offending values of the key as written in collectd.conf
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+
+    pub(crate) fn with_location(mut self, path: String, value: Option<String>) -> Self {
+        self.path = path;
+        self.value = value;
+        self
+    }
+}
+
+impl From<DeError> for Error {
+    fn from(kind: DeError) -> Self {
+        Error {
+            kind,
+            path: String::new(),
+            value: None,
+        }
+    }
+}
 
 impl de::Error for Error {
     fn custom<T: Display>(msg: T) -> Self {
-        Error(DeError::SerdeError(msg.to_string()))
+        Error::from(DeError::SerdeError(msg.to_string()))
     }
 }
 
@@ -36,7 +79,22 @@ impl error::Error for Error {
 
 impl Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self.0 {
+        if !self.path.is_empty() {
+            write!(f, "{}: ", self.path)?;
+        }
+
+        write!(f, "{}", self.kind)?;
+        if let Some(ref value) = self.value {
+            write!(f, ", found: {}", value)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for DeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
             DeError::NoMoreValuesLeft => write!(f, "no more values left, this should never happen"),
             DeError::SerdeError(ref s) => write!(f, "error from deserialization: {}", s),
             DeError::ExpectSingleValue => write!(f, "expecting values to contain a single entry"),
@@ -46,11 +104,24 @@ impl Display for Error {
             }
             DeError::ExpectBoolean => write!(f, "expecting boolean"),
             DeError::ExpectNumber => write!(f, "expecting number"),
+            DeError::NumberOutOfRange { target, number } => write!(
+                f,
+                "number {} cannot be represented as {} without truncation",
+                number, target
+            ),
             DeError::ExpectStruct => write!(f, "expecting struct"),
             DeError::ExpectObject => write!(f, "needs an object to deserialize a struct"),
             DeError::DataTypeNotSupported => {
                 write!(f, "could not deserialize as datatype not supported")
             }
+            DeError::ExpectVariant => write!(
+                f,
+                "expecting an enum variant as the first value or the only key of a block"
+            ),
+            DeError::ExpectLabel => write!(f, "expecting a labeled block"),
+            DeError::UnexpectedValues(ref s) => {
+                write!(f, "expecting no values after the unit variant `{}`", s)
+            }
         }
     }
 }
@@ -0,0 +1,113 @@
+use serde::de::{self, Deserialize, Deserializer, MapAccess, Visitor};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// The struct name that signals the config deserializer to deserialize a `Labeled`
+pub(crate) const LABELED: &str = "$collectd_plugin::Labeled";
+pub(crate) const LABELED_FIELDS: &[&str] = &["label", "value"];
+
+/// A block along with the values in its opening tag, which collectd plugins commonly use to name
+/// instances (eg: `<Database "prod">`). The label is a `String` by default, but it can be any
+/// type that can be deserialized from the values (eg: `Vec<String>` for several labels).
+///
+/// ```
+/// use collectd_plugin::de::{from_collectd, Labeled};
+/// use collectd_plugin::{ConfigItem, ConfigValue};
+/// use serde::Deserialize;
+/// use std::collections::HashMap;
+///
+/// #[derive(Deserialize)]
+/// #[serde(rename_all = "PascalCase")]
+/// struct Database {
+///     host: String,
+/// }
+///
+/// #[derive(Deserialize)]
+/// #[serde(rename_all = "PascalCase")]
+/// struct Config {
+///     // Either keep the label alongside the block ...
+///     database: Vec<Labeled<Database>>,
+///
+///     // ... or key the blocks by their label
+///     cache: HashMap<String, Database>,
+/// }
+///
+/// let block = |key, label| ConfigItem {
+///     key,
+///     values: vec![ConfigValue::String(label)],
+///     children: vec![ConfigItem {
+///         key: "Host",
+///         values: vec![ConfigValue::String("localhost")],
+///         children: vec![],
+///     }],
+/// };
+///
+/// // <Database "prod"> Host "localhost" </Database>
+/// // <Cache "redis"> Host "localhost" </Cache>
+/// let items = vec![block("Database", "prod"), block("Cache", "redis")];
+///
+/// let config: Config = from_collectd(&items).unwrap();
+/// assert_eq!(config.database[0].label, "prod");
+/// assert_eq!(config.database[0].value.host, "localhost");
+/// assert_eq!(config.cache["redis"].host, "localhost");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Labeled<T, L = String> {
+    /// The values in the block's opening tag
+    pub label: L,
+
+    /// The contents of the block
+    pub value: T,
+}
+
+struct LabeledVisitor<T, L> {
+    marker: PhantomData<fn() -> (T, L)>,
+}
+
+impl<'de, T, L> Visitor<'de> for LabeledVisitor<T, L>
+where
+    T: Deserialize<'de>,
+    L: Deserialize<'de>,
+{
+    type Value = Labeled<T, L>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a labeled block")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Labeled<T, L>, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut label = None;
+        let mut value = None;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "label" => label = Some(map.next_value()?),
+                "value" => value = Some(map.next_value()?),
+                _ => return Err(de::Error::unknown_field(&key, LABELED_FIELDS)),
+            }
+        }
+
+        Ok(Labeled {
+            label: label.ok_or_else(|| de::Error::missing_field("label"))?,
+            value: value.ok_or_else(|| de::Error::missing_field("value"))?,
+        })
+    }
+}
+
+impl<'de, T, L> Deserialize<'de> for Labeled<T, L>
+where
+    T: Deserialize<'de>,
+    L: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Labeled<T, L>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let visitor = LabeledVisitor {
+            marker: PhantomData,
+        };
+        deserializer.deserialize_struct(LABELED, LABELED_FIELDS, visitor)
+    }
+}
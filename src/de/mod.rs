@@ -1,12 +1,17 @@
 mod deconfig;
 mod errors;
+mod labeled;
 mod level;
 pub use self::errors::*;
+pub use self::labeled::Labeled;
+use self::labeled::{LABELED, LABELED_FIELDS};
 
 use self::deconfig::*;
 use crate::api::ConfigItem;
+use serde::de::value::BorrowedStrDeserializer;
 use serde::de::{self, Deserialize, DeserializeSeed, MapAccess, SeqAccess, Visitor};
 use serde::forward_to_deserialize_any;
+use std::fmt::Write;
 
 /// Serde documentation shadows the std's Result type which can be really confusing for Rust
 /// newcomers, so we compromise by creating an alias but prefixing with "De" to make it standout.
@@ -27,18 +32,22 @@ enum DeType<'a> {
 
 pub struct Deserializer<'a> {
     depth: Vec<DeType<'a>>,
+
+    /// Whether numbers are cast to integers even if they are truncated or saturated
+    lossy: bool,
 }
 
 impl<'a> Deserializer<'a> {
-    fn from_collectd(input: Vec<(&'a str, Vec<DeConfig<'a>>)>) -> Self {
+    fn from_collectd(input: Vec<(&'a str, Vec<DeConfig<'a>>)>, lossy: bool) -> Self {
         Deserializer {
             depth: vec![DeType::Struct(input, 0)],
+            lossy,
         }
     }
 
     fn current(&self) -> DeResult<&DeType<'a>> {
         if self.depth.is_empty() {
-            return Err(Error(DeError::NoMoreValuesLeft));
+            return Err(Error::from(DeError::NoMoreValuesLeft));
         }
 
         Ok(&self.depth[self.depth.len() - 1])
@@ -48,13 +57,13 @@ impl<'a> Deserializer<'a> {
         match *self.current()? {
             DeType::Item(_, ref values) => {
                 if values.len() != 1 {
-                    return Err(Error(DeError::ExpectSingleValue));
+                    return Err(Error::from(DeError::ExpectSingleValue));
                 }
 
                 Ok(&values[0])
             }
             DeType::Seq(ref items, ind) => Ok(&items[ind]),
-            _ => Err(Error(DeError::ExpectSingleValue)),
+            _ => Err(Error::from(DeError::ExpectSingleValue)),
         }
     }
 
@@ -62,7 +71,7 @@ impl<'a> Deserializer<'a> {
         if let DeConfig::String(x) = *self.grab_val()? {
             Ok(x)
         } else {
-            Err(Error(DeError::ExpectString))
+            Err(Error::from(DeError::ExpectString))
         }
     }
 
@@ -70,7 +79,7 @@ impl<'a> Deserializer<'a> {
         if let DeConfig::Boolean(x) = *self.grab_val()? {
             Ok(x)
         } else {
-            Err(Error(DeError::ExpectBoolean))
+            Err(Error::from(DeError::ExpectBoolean))
         }
     }
 
@@ -78,7 +87,21 @@ impl<'a> Deserializer<'a> {
         if let DeConfig::Number(x) = *self.grab_val()? {
             Ok(x)
         } else {
-            Err(Error(DeError::ExpectNumber))
+            Err(Error::from(DeError::ExpectNumber))
+        }
+    }
+
+    /// Grabs a number that is checked to be an integer within `min` and `max` (inclusive) so
+    /// that the cast to the target type is lossless
+    fn grab_integer(&self, target: &'static str, min: f64, max: f64) -> DeResult<f64> {
+        let x = self.grab_number()?;
+
+        // The max of 64 bit integers is rounded up to the next power of two as a float, so the
+        // upper bound is exclusive of one past the max
+        if self.lossy || (x.fract() == 0.0 && x >= min && x < max + 1.0) {
+            Ok(x)
+        } else {
+            Err(Error::from(DeError::NumberOutOfRange { target, number: x }))
         }
     }
 
@@ -86,6 +109,48 @@ impl<'a> Deserializer<'a> {
         self.depth.pop();
     }
 
+    /// The path to the value that is being deserialized. Since the depth is not unwound when an
+    /// error is returned, this is where the error occurred. The index of a sequence is only
+    /// included if the key is repeated.
+    fn path(&self) -> String {
+        let mut path = String::new();
+        for t in &self.depth {
+            match *t {
+                DeType::Item(key, _) => {
+                    if !path.is_empty() {
+                        path.push('.');
+                    }
+                    path.push_str(key);
+                }
+                DeType::Seq(ref items, ind) => match items[ind] {
+                    DeConfig::Labeled(ref labels, _) if !labels.is_empty() => {
+                        let _ = write!(path, "[{}]", labels[0]);
+                    }
+                    _ if items.len() > 1 => {
+                        let _ = write!(path, "[{}]", ind);
+                    }
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+        path
+    }
+
+    /// The values that are being deserialized, formatted as they appear in collectd.conf. An item
+    /// without values (eg: `Host` on a line of its own) has nothing to show.
+    fn values(&self) -> Option<String> {
+        let values = match *self.current().ok()? {
+            DeType::Item(_, ref values) if values.is_empty() => return None,
+            DeType::Item(_, ref values) => &values[..],
+            DeType::Seq(ref items, ind) => &items[ind..=ind],
+            DeType::Struct(..) => return None,
+        };
+
+        let values: Vec<String> = values.iter().map(|x| x.to_string()).collect();
+        Some(values.join(" "))
+    }
+
     fn push(&mut self, pos: usize) {
         // Find the parent -- it's either the tail element of depth or penultimate.
         let cur = if pos == 0 { 1 } else { 2 };
@@ -106,6 +171,31 @@ impl<'a> Deserializer<'a> {
         }
     }
 
+    /// Visits the children of a block (or the top level config) as a map
+    fn visit_object<V>(&mut self, visitor: V) -> DeResult<V::Value>
+    where
+        V: Visitor<'a>,
+    {
+        let children = match *self.current()? {
+            DeType::Struct(ref values, _ind) => {
+                let len = values.len();
+                return visitor.visit_map(FieldSeparated::new(self, len));
+            }
+            DeType::Item(_key, ref values) if values.len() == 1 => values[0].children().cloned(),
+            DeType::Seq(ref values, ind) => values[ind].children().cloned(),
+            DeType::Item(..) => None,
+        };
+
+        let children = children.ok_or(Error::from(DeError::ExpectObject))?;
+
+        // Push the children onto the stack and pop back up once they have been visited
+        let len = children.len();
+        self.depth.push(DeType::Struct(children, 0));
+        let res = visitor.visit_map(FieldSeparated::new(self, len))?;
+        self.pop();
+        Ok(res)
+    }
+
     fn push_seq(&mut self, pos: usize) {
         // Find the parent -- it's either the tail element of depth or penultimate.
         let cur = if pos == 0 { 1 } else { 2 };
@@ -127,13 +217,33 @@ impl<'a> Deserializer<'a> {
     }
 }
 
+/// Deserializes the config. Numbers are only deserialized into integer types when they are
+/// integral and within the range of the type (eg: `Port 70000` is an error for a `u16`).
 pub fn from_collectd<'a, T>(s: &'a [ConfigItem<'a>]) -> DeResult<T>
+where
+    T: Deserialize<'a>,
+{
+    deserialize(s, false)
+}
+
+/// Deserializes the config like `from_collectd` except that numbers are cast to integer types
+/// with `as`, so fractions are truncated and out of range numbers saturate (eg: `Threads 2.5`
+/// becomes `2` and `Port -1` becomes `0`). Only for configs that rely on this behavior.
+pub fn from_collectd_lossy<'a, T>(s: &'a [ConfigItem<'a>]) -> DeResult<T>
+where
+    T: Deserialize<'a>,
+{
+    deserialize(s, true)
+}
+
+fn deserialize<'a, T>(s: &'a [ConfigItem<'a>], lossy: bool) -> DeResult<T>
 where
     T: Deserialize<'a>,
 {
     let props = from_config(s);
-    let mut deserializer = Deserializer::from_collectd(props);
+    let mut deserializer = Deserializer::from_collectd(props, lossy);
     T::deserialize(&mut deserializer)
+        .map_err(|e| e.with_location(deserializer.path(), deserializer.values()))
 }
 
 impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
@@ -166,63 +276,79 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        self.grab_number().and_then(|x| visitor.visit_i8(x as i8))
+        self.grab_integer("i8", i8::MIN as f64, i8::MAX as f64)
+            .and_then(|x| visitor.visit_i8(x as i8))
     }
 
     fn deserialize_i16<V>(self, visitor: V) -> DeResult<V::Value>
     where
         V: Visitor<'de>,
     {
-        self.grab_number().and_then(|x| visitor.visit_i16(x as i16))
+        self.grab_integer("i16", i16::MIN as f64, i16::MAX as f64)
+            .and_then(|x| visitor.visit_i16(x as i16))
     }
 
     fn deserialize_i32<V>(self, visitor: V) -> DeResult<V::Value>
     where
         V: Visitor<'de>,
     {
-        self.grab_number().and_then(|x| visitor.visit_i32(x as i32))
+        self.grab_integer("i32", i32::MIN as f64, i32::MAX as f64)
+            .and_then(|x| visitor.visit_i32(x as i32))
     }
 
     fn deserialize_i64<V>(self, visitor: V) -> DeResult<V::Value>
     where
         V: Visitor<'de>,
     {
-        self.grab_number().and_then(|x| visitor.visit_i64(x as i64))
+        self.grab_integer("i64", i64::MIN as f64, i64::MAX as f64)
+            .and_then(|x| visitor.visit_i64(x as i64))
     }
 
     fn deserialize_u8<V>(self, visitor: V) -> DeResult<V::Value>
     where
         V: Visitor<'de>,
     {
-        self.grab_number().and_then(|x| visitor.visit_u8(x as u8))
+        self.grab_integer("u8", u8::MIN as f64, u8::MAX as f64)
+            .and_then(|x| visitor.visit_u8(x as u8))
     }
 
     fn deserialize_u16<V>(self, visitor: V) -> DeResult<V::Value>
     where
         V: Visitor<'de>,
     {
-        self.grab_number().and_then(|x| visitor.visit_u16(x as u16))
+        self.grab_integer("u16", u16::MIN as f64, u16::MAX as f64)
+            .and_then(|x| visitor.visit_u16(x as u16))
     }
 
     fn deserialize_u32<V>(self, visitor: V) -> DeResult<V::Value>
     where
         V: Visitor<'de>,
     {
-        self.grab_number().and_then(|x| visitor.visit_u32(x as u32))
+        self.grab_integer("u32", u32::MIN as f64, u32::MAX as f64)
+            .and_then(|x| visitor.visit_u32(x as u32))
     }
 
     fn deserialize_u64<V>(self, visitor: V) -> DeResult<V::Value>
     where
         V: Visitor<'de>,
     {
-        self.grab_number().and_then(|x| visitor.visit_u64(x as u64))
+        self.grab_integer("u64", u64::MIN as f64, u64::MAX as f64)
+            .and_then(|x| visitor.visit_u64(x as u64))
     }
 
     fn deserialize_f32<V>(self, visitor: V) -> DeResult<V::Value>
     where
         V: Visitor<'de>,
     {
-        self.grab_number().and_then(|x| visitor.visit_f32(x as f32))
+        let x = self.grab_number()?;
+        if !self.lossy && x.is_finite() && (x as f32).is_infinite() {
+            return Err(Error::from(DeError::NumberOutOfRange {
+                target: "f32",
+                number: x,
+            }));
+        }
+
+        visitor.visit_f32(x as f32)
     }
 
     fn deserialize_f64<V>(self, visitor: V) -> DeResult<V::Value>
@@ -245,7 +371,7 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         self.grab_string().and_then(|x| {
             if x.len() != 1 {
-                Err(Error(DeError::ExpectChar(String::from(x))))
+                Err(Error::from(DeError::ExpectChar(String::from(x))))
             } else {
                 visitor.visit_char(x.chars().next().unwrap())
             }
@@ -256,20 +382,10 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        let v = &self.depth[self.depth.len() - 1];
-        match *v {
-            DeType::Item(key, _) => visitor.visit_borrowed_str(key),
-            DeType::Seq(ref x, pos) if pos == 0 => {
-                if let DeConfig::String(ss) = x[pos] {
-                    visitor.visit_borrowed_str(ss)
-                } else {
-                    Err(de::Error::custom("expected the first element, when deserializing for an identifier, to be a string"))
-                }
-            }
-            _ => Err(de::Error::custom(
-                "expected an item when deserializing identifier",
-            )),
-        }
+        // Keys and enum variants are deserialized on their own, so an identifier here is a value
+        // (eg: the tag of an internally tagged enum)
+        self.grab_string()
+            .and_then(|x| visitor.visit_borrowed_str(x))
     }
 
     fn deserialize_seq<V>(self, visitor: V) -> DeResult<V::Value>
@@ -282,43 +398,58 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
             return Err(de::Error::custom("expected an item when deserializing seq"));
         };
 
-        visitor.visit_seq(SeqSeparated::new(self, len))
+        // Tuples don't read past their last element, so restore the depth ourselves
+        let depth = self.depth.len();
+        let mut seq = SeqSeparated::new(self, len);
+        let res = visitor.visit_seq(&mut seq)?;
+        let read = seq.item_pos;
+        self.depth.truncate(depth);
+
+        // Values that a tuple (or tuple variant) didn't read would otherwise be silently ignored
+        if read < len {
+            let expected = format!("{} values", read);
+            return Err(de::Error::invalid_length(len, &expected.as_str()));
+        }
+
+        Ok(res)
     }
 
     fn deserialize_struct<V>(
         self,
-        _name: &'static str,
+        name: &'static str,
         _fields: &'static [&'static str],
         visitor: V,
     ) -> DeResult<V::Value>
     where
         V: Visitor<'de>,
     {
-        // A small hack to remember if we dive into a sequence's children. Since we're going down
-        // two levels, we need to remember to pop back up when were down the children.
-        let mut to_pop = false;
-
-        let t = match self.current()?.clone() {
-            DeType::Struct(ref values, _ind) => Some(values.len()),
-            DeType::Seq(ref values, ind) => {
-                if let DeConfig::Object(ref obj) = values[ind] {
-                    // Push the children onto the stack
-                    let s = DeType::Struct(obj.clone(), 0);
-                    self.depth.push(s);
-                    to_pop = true;
-                    Some(obj.len())
-                } else {
-                    return Err(Error(DeError::ExpectObject));
-                }
+        if name == LABELED {
+            return visitor.visit_map(LabeledAccess::new(self));
+        }
+
+        if let DeType::Item(_key, ref values) = *self.current()? {
+            if !matches!(values[..], [ref x] if x.children().is_some()) {
+                return visitor.visit_map(FieldSeparated::new(self, 0));
             }
-            _ => None,
-        };
+        }
+
+        self.visit_object(visitor)
+    }
 
-        let res = visitor.visit_map(FieldSeparated::new(self, t.unwrap_or(0)))?;
-        if to_pop {
-            self.pop();
+    fn deserialize_map<V>(self, visitor: V) -> DeResult<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        // Labeled blocks are keyed by their label
+        if let DeType::Item(_key, ref values) = *self.current()? {
+            let labeled = values.iter().all(|x| matches!(*x, DeConfig::Labeled(..)));
+            if labeled {
+                let len = values.len();
+                return visitor.visit_map(LabelSeparated::new(self, len));
+            }
         }
-        Ok(res)
+
+        self.visit_object(visitor)
     }
 
     fn deserialize_ignored_any<V>(self, visitor: V) -> DeResult<V::Value>
@@ -328,11 +459,34 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
         visitor.visit_none()
     }
 
-    fn deserialize_any<V>(self, _visitor: V) -> DeResult<V::Value>
+    fn deserialize_any<V>(self, visitor: V) -> DeResult<V::Value>
     where
         V: Visitor<'de>,
     {
-        Err(Error(DeError::DataTypeNotSupported))
+        match *self.current()? {
+            DeType::Struct(..) => return self.visit_object(visitor),
+            DeType::Item(_key, ref values) if values.len() != 1 => {
+                return self.deserialize_seq(visitor)
+            }
+            _ => {}
+        }
+
+        match *self.grab_val()? {
+            DeConfig::Boolean(x) => visitor.visit_bool(x),
+            DeConfig::String(x) => visitor.visit_borrowed_str(x),
+            DeConfig::Object(_) | DeConfig::Labeled(..) => self.visit_object(visitor),
+
+            // Collectd only has floating point numbers, but integral numbers are passed as
+            // integers so that they can be deserialized into integer types when the type isn't
+            // known upfront (eg: untagged enums)
+            DeConfig::Number(x) if x.fract() == 0.0 && x >= 0.0 && x < u64::MAX as f64 => {
+                visitor.visit_u64(x as u64)
+            }
+            DeConfig::Number(x) if x.fract() == 0.0 && x < 0.0 && x >= i64::MIN as f64 => {
+                visitor.visit_i64(x as i64)
+            }
+            DeConfig::Number(x) => visitor.visit_f64(x),
+        }
     }
 
     fn deserialize_newtype_struct<V>(self, _name: &str, visitor: V) -> DeResult<V::Value>
@@ -351,44 +505,89 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        if let DeType::Item(_key, ref v) = self.current()?.clone() {
-            if v.len() != 1 {
-                return Err(de::Error::custom(
-                    "expected enum item to have a single item list",
-                ));
-            }
+        // The variant is either the first value of the item, where the remaining values are
+        // the variant's contents, or a block with a single child whose key is the variant
+        let original = self.current()?.clone();
+        let (variant, contents, nested) = match original {
+            DeType::Item(key, ref values) => match values[..] {
+                [DeConfig::String(tag), ref rest @ ..] => {
+                    (tag, DeType::Item(key, rest.to_vec()), false)
+                }
+                [DeConfig::Labeled(ref labels, ref children)] => match labels[..] {
+                    [DeConfig::String(tag), ref rest @ ..] => {
+                        let mut rest = rest.to_vec();
+                        rest.push(DeConfig::Object(children.clone()));
+                        (tag, DeType::Item(key, rest), false)
+                    }
+                    _ => return Err(Error::from(DeError::ExpectVariant)),
+                },
+                [DeConfig::Object(ref obj)] if obj.len() == 1 => {
+                    let (tag, ref rest) = obj[0];
+                    (tag, DeType::Item(tag, rest.clone()), true)
+                }
+                _ => return Err(Error::from(DeError::ExpectVariant)),
+            },
+            DeType::Seq(ref values, ind) => match values[ind] {
+                DeConfig::String(tag) => (tag, DeType::Item(tag, Vec::new()), true),
+                DeConfig::Labeled(ref labels, ref children) => match labels[..] {
+                    [DeConfig::String(tag), ref rest @ ..] => {
+                        let mut rest = rest.to_vec();
+                        rest.push(DeConfig::Object(children.clone()));
+                        (tag, DeType::Item(tag, rest), true)
+                    }
+                    _ => return Err(Error::from(DeError::ExpectVariant)),
+                },
+                DeConfig::Object(ref obj) if obj.len() == 1 => {
+                    let (tag, ref rest) = obj[0];
+                    (tag, DeType::Item(tag, rest.clone()), true)
+                }
+                _ => return Err(Error::from(DeError::ExpectVariant)),
+            },
+            DeType::Struct(..) => return Err(Error::from(DeError::ExpectVariant)),
+        };
 
-            // With a unit variant enum, it needs to take a look at the identifier, so in the case
-            // deserializing JSON {"level": "INFO"} serde will attempt to deserialize two identifiers
-            // in a row (first "level" and then "INFO"). Since we don't want to re-read the "level"
-            // identifier we simulate sequence. Not the most elegant solution, but it works.
-            self.push_seq(0);
-            let res = visitor.visit_enum(UnitVariantAccess::new(self))?;
-            self.pop();
-            Ok(res)
+        // A nested variant is pushed so that it is part of the path, otherwise the variant's
+        // contents temporarily take the place of the item's values
+        let len = self.depth.len();
+        if nested {
+            self.depth.push(contents);
         } else {
-            Err(Error(DeError::ExpectStruct))
+            self.depth[len - 1] = contents;
         }
+
+        let res = visitor.visit_enum(VariantAccess::new(self, variant))?;
+
+        self.depth.truncate(len);
+        self.depth[len - 1] = original;
+        Ok(res)
     }
 
     forward_to_deserialize_any! {
         bytes
         byte_buf unit unit_struct tuple
-        tuple_struct map
+        tuple_struct
     }
 }
 
-struct UnitVariantAccess<'a, 'de: 'a> {
+struct VariantAccess<'a, 'de: 'a> {
     de: &'a mut Deserializer<'de>,
+    variant: &'de str,
 }
 
-impl<'a, 'de: 'a> UnitVariantAccess<'a, 'de> {
-    fn new(de: &'a mut Deserializer<'de>) -> Self {
-        UnitVariantAccess { de }
+impl<'a, 'de: 'a> VariantAccess<'a, 'de> {
+    fn new(de: &'a mut Deserializer<'de>, variant: &'de str) -> Self {
+        VariantAccess { de, variant }
+    }
+
+    fn contents(&self) -> DeResult<&[DeConfig<'de>]> {
+        match *self.de.current()? {
+            DeType::Item(_key, ref values) => Ok(values),
+            _ => Err(Error::from(DeError::NoMoreValuesLeft)),
+        }
     }
 }
 
-impl<'de> de::EnumAccess<'de> for UnitVariantAccess<'_, 'de> {
+impl<'de> de::EnumAccess<'de> for VariantAccess<'_, 'de> {
     type Error = Error;
     type Variant = Self;
 
@@ -396,37 +595,43 @@ impl<'de> de::EnumAccess<'de> for UnitVariantAccess<'_, 'de> {
     where
         V: de::DeserializeSeed<'de>,
     {
-        let variant = seed.deserialize(&mut *self.de)?;
+        let variant = seed.deserialize(BorrowedStrDeserializer::<Error>::new(self.variant))?;
         Ok((variant, self))
     }
 }
 
-impl<'de> de::VariantAccess<'de> for UnitVariantAccess<'_, 'de> {
+impl<'de> de::VariantAccess<'de> for VariantAccess<'_, 'de> {
     type Error = Error;
 
     fn unit_variant(self) -> DeResult<()> {
-        Ok(())
+        if self.contents()?.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::from(DeError::UnexpectedValues(String::from(
+                self.variant,
+            ))))
+        }
     }
 
-    fn newtype_variant_seed<T>(self, _seed: T) -> DeResult<T::Value>
+    fn newtype_variant_seed<T>(self, seed: T) -> DeResult<T::Value>
     where
         T: de::DeserializeSeed<'de>,
     {
-        Err(de::Error::custom("newtype variant"))
+        seed.deserialize(&mut *self.de)
     }
 
-    fn tuple_variant<V>(self, _len: usize, _visitor: V) -> DeResult<V::Value>
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> DeResult<V::Value>
     where
         V: de::Visitor<'de>,
     {
-        Err(de::Error::custom("tuple variant"))
+        de::Deserializer::deserialize_seq(&mut *self.de, visitor)
     }
 
-    fn struct_variant<V>(self, _fields: &'static [&'static str], _visitor: V) -> DeResult<V::Value>
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> DeResult<V::Value>
     where
         V: de::Visitor<'de>,
     {
-        Err(de::Error::custom("struct variant"))
+        self.de.visit_object(visitor)
     }
 }
 
@@ -463,7 +668,16 @@ impl<'de> MapAccess<'de> for FieldSeparated<'_, 'de> {
 
         self.de.push(self.item_pos);
         self.item_pos += 1;
-        seed.deserialize(&mut *self.de).map(Some)
+
+        // Keys are always strings, so they are deserialized on their own, otherwise maps would
+        // deserialize keys from the values of the item
+        let key = match *self.de.current()? {
+            DeType::Item(key, _) => key,
+            _ => return Err(Error::from(DeError::NoMoreValuesLeft)),
+        };
+
+        seed.deserialize(BorrowedStrDeserializer::new(key))
+            .map(Some)
     }
 
     fn next_value_seed<V>(&mut self, seed: V) -> DeResult<V::Value>
@@ -474,6 +688,123 @@ impl<'de> MapAccess<'de> for FieldSeparated<'_, 'de> {
     }
 }
 
+/// Deserializes the labeled blocks of an item as a map from their label to their children
+struct LabelSeparated<'a, 'de: 'a> {
+    de: &'a mut Deserializer<'de>,
+    item_count: usize,
+    item_pos: usize,
+}
+
+impl<'a, 'de> LabelSeparated<'a, 'de> {
+    fn new(de: &'a mut Deserializer<'de>, item_count: usize) -> Self {
+        LabelSeparated {
+            de,
+            item_count,
+            item_pos: 0,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for LabelSeparated<'_, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> DeResult<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if self.item_pos == self.item_count {
+            if self.item_count != 0 {
+                self.de.pop();
+            }
+            return Ok(None);
+        }
+
+        self.de.push_seq(self.item_pos);
+        self.item_pos += 1;
+
+        let label = match *self.de.current()? {
+            DeType::Seq(ref values, ind) => match values[ind] {
+                DeConfig::Labeled(ref labels, _) => match labels[..] {
+                    [DeConfig::String(label)] => label,
+                    _ => return Err(Error::from(DeError::ExpectString)),
+                },
+                _ => return Err(Error::from(DeError::ExpectLabel)),
+            },
+            _ => return Err(Error::from(DeError::NoMoreValuesLeft)),
+        };
+
+        seed.deserialize(BorrowedStrDeserializer::new(label))
+            .map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> DeResult<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        seed.deserialize(&mut *self.de)
+    }
+}
+
+/// Deserializes a block as a `Labeled`, which has a field for the block's label(s) and one for
+/// the block's children
+struct LabeledAccess<'a, 'de: 'a> {
+    de: &'a mut Deserializer<'de>,
+    field: usize,
+}
+
+impl<'a, 'de> LabeledAccess<'a, 'de> {
+    fn new(de: &'a mut Deserializer<'de>) -> Self {
+        LabeledAccess { de, field: 0 }
+    }
+}
+
+impl<'de> MapAccess<'de> for LabeledAccess<'_, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> DeResult<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let key = match LABELED_FIELDS.get(self.field) {
+            Some(key) => key,
+            None => return Ok(None),
+        };
+
+        self.field += 1;
+        seed.deserialize(BorrowedStrDeserializer::new(key))
+            .map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> DeResult<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        // The value is deserialized from the block itself, while the label is deserialized from
+        // the block's labels
+        if LABELED_FIELDS[self.field - 1] == "value" {
+            return seed.deserialize(&mut *self.de);
+        }
+
+        let block = match *self.de.current()? {
+            DeType::Item(_key, ref values) if values.len() == 1 => values[0].clone(),
+            DeType::Seq(ref values, ind) => values[ind].clone(),
+            _ => return Err(Error::from(DeError::ExpectObject)),
+        };
+
+        let labels = match block {
+            DeConfig::Labeled(labels, _) => labels,
+            DeConfig::Object(_) => Vec::new(),
+            _ => return Err(Error::from(DeError::ExpectObject)),
+        };
+
+        let depth = self.de.depth.len();
+        self.de.depth.push(DeType::Item(LABELED_FIELDS[0], labels));
+        let res = seed.deserialize(&mut *self.de)?;
+        self.de.depth.truncate(depth);
+        Ok(res)
+    }
+}
+
 struct SeqSeparated<'a, 'de: 'a> {
     de: &'a mut Deserializer<'de>,
     item_count: usize,
@@ -516,6 +847,7 @@ mod tests {
     use super::*;
     use crate::api::LogLevel;
     use serde::Deserialize;
+    use std::collections::HashMap;
 
     #[test]
     fn test_serde_simple_bool() {
@@ -562,6 +894,106 @@ mod tests {
         assert_eq!(MyStruct { my_int: 1 }, actual);
     }
 
+    #[test]
+    fn test_serde_number_out_of_range() {
+        #[derive(Deserialize, PartialEq, Eq, Debug)]
+        struct MyStruct {
+            port: u16,
+        }
+
+        let item = |x| {
+            vec![ConfigItem {
+                key: "port",
+                values: vec![ConfigValue::Number(x)],
+                children: vec![],
+            }]
+        };
+
+        for &x in &[-1.0, 70000.0, 2.5, f64::NAN] {
+            let items = item(x);
+            let err = from_collectd::<MyStruct>(&items).unwrap_err();
+            match *err.kind() {
+                DeError::NumberOutOfRange { target, number } => {
+                    assert_eq!(target, "u16");
+                    assert!(number == x || number.is_nan());
+                }
+                ref e => panic!("unexpected error: {}", e),
+            }
+        }
+
+        let items = item(70000.0);
+        let err = from_collectd::<MyStruct>(&items).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "port: number 70000 cannot be represented as u16 without truncation, found: 70000"
+        );
+
+        let items = item(65535.0);
+        assert_eq!(from_collectd(&items).ok(), Some(MyStruct { port: 65535 }));
+
+        let items = item(2.5);
+        assert_eq!(from_collectd_lossy(&items).ok(), Some(MyStruct { port: 2 }));
+
+        let items = item(-1.0);
+        assert_eq!(from_collectd_lossy(&items).ok(), Some(MyStruct { port: 0 }));
+    }
+
+    #[test]
+    fn test_serde_number_bounds() {
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct MyStruct {
+            a: i64,
+            b: u64,
+            c: i8,
+            d: f32,
+        }
+
+        let items = |a, b, c, d| {
+            vec![
+                ConfigItem {
+                    key: "a",
+                    values: vec![ConfigValue::Number(a)],
+                    children: vec![],
+                },
+                ConfigItem {
+                    key: "b",
+                    values: vec![ConfigValue::Number(b)],
+                    children: vec![],
+                },
+                ConfigItem {
+                    key: "c",
+                    values: vec![ConfigValue::Number(c)],
+                    children: vec![],
+                },
+                ConfigItem {
+                    key: "d",
+                    values: vec![ConfigValue::Number(d)],
+                    children: vec![],
+                },
+            ]
+        };
+
+        let ok = items(i64::MIN as f64, 2f64.powi(63), -128.0, 1.5);
+        assert_eq!(
+            from_collectd::<MyStruct>(&ok).unwrap(),
+            MyStruct {
+                a: i64::MIN,
+                b: 1 << 63,
+                c: -128,
+                d: 1.5,
+            }
+        );
+
+        for bad in &[
+            items(2f64.powi(63), 0.0, 0.0, 0.0),
+            items(0.0, 2f64.powi(64), 0.0, 0.0),
+            items(0.0, 0.0, -129.0, 0.0),
+            items(0.0, 0.0, 0.0, 1e300),
+        ] {
+            assert!(from_collectd::<MyStruct>(bad).is_err());
+        }
+    }
+
     #[test]
     fn test_serde_simple_string() {
         #[derive(Deserialize, PartialEq, Eq, Debug)]
@@ -1068,4 +1500,530 @@ mod tests {
             actual
         );
     }
+
+    #[test]
+    fn test_serde_map() {
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct MyStruct {
+            labels: HashMap<String, String>,
+            weights: HashMap<String, f64>,
+        }
+
+        let items = vec![
+            ConfigItem {
+                key: "labels",
+                values: vec![],
+                children: vec![
+                    ConfigItem {
+                        key: "env",
+                        values: vec![ConfigValue::String("prod")],
+                        children: vec![],
+                    },
+                    ConfigItem {
+                        key: "region",
+                        values: vec![ConfigValue::String("us-east")],
+                        children: vec![],
+                    },
+                ],
+            },
+            ConfigItem {
+                key: "weights",
+                values: vec![],
+                children: vec![ConfigItem {
+                    key: "cpu",
+                    values: vec![ConfigValue::Number(0.5)],
+                    children: vec![],
+                }],
+            },
+        ];
+
+        let actual: MyStruct = from_collectd(&items).unwrap();
+        let mut labels = HashMap::new();
+        labels.insert(String::from("env"), String::from("prod"));
+        labels.insert(String::from("region"), String::from("us-east"));
+        let mut weights = HashMap::new();
+        weights.insert(String::from("cpu"), 0.5);
+        assert_eq!(actual, MyStruct { labels, weights });
+    }
+
+    #[test]
+    fn test_serde_untagged() {
+        #[derive(Deserialize, PartialEq, Debug)]
+        #[serde(untagged)]
+        enum Setting {
+            Flag(bool),
+            Port(u16),
+            Ratio(f64),
+            Name(String),
+            Names(Vec<String>),
+            Block { host: String },
+        }
+
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct MyStruct {
+            a: Setting,
+            b: Setting,
+            c: Setting,
+            d: Setting,
+            e: Setting,
+            f: Setting,
+        }
+
+        let item = |key, values| ConfigItem {
+            key,
+            values,
+            children: vec![],
+        };
+
+        let items = vec![
+            item("a", vec![ConfigValue::Boolean(true)]),
+            item("b", vec![ConfigValue::Number(2003.0)]),
+            item("c", vec![ConfigValue::Number(0.5)]),
+            item("d", vec![ConfigValue::String("x")]),
+            item(
+                "e",
+                vec![ConfigValue::String("x"), ConfigValue::String("y")],
+            ),
+            ConfigItem {
+                key: "f",
+                values: vec![],
+                children: vec![item("host", vec![ConfigValue::String("localhost")])],
+            },
+        ];
+
+        let actual: MyStruct = from_collectd(&items).unwrap();
+        assert_eq!(
+            actual,
+            MyStruct {
+                a: Setting::Flag(true),
+                b: Setting::Port(2003),
+                c: Setting::Ratio(0.5),
+                d: Setting::Name(String::from("x")),
+                e: Setting::Names(vec![String::from("x"), String::from("y")]),
+                f: Setting::Block {
+                    host: String::from("localhost")
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_serde_flatten() {
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct MyStruct {
+            host: String,
+            #[serde(flatten)]
+            rest: HashMap<String, i32>,
+        }
+
+        let items = vec![
+            ConfigItem {
+                key: "host",
+                values: vec![ConfigValue::String("localhost")],
+                children: vec![],
+            },
+            ConfigItem {
+                key: "port",
+                values: vec![ConfigValue::Number(2003.0)],
+                children: vec![],
+            },
+        ];
+
+        let actual: MyStruct = from_collectd(&items).unwrap();
+        let mut rest = HashMap::new();
+        rest.insert(String::from("port"), 2003);
+        assert_eq!(
+            actual,
+            MyStruct {
+                host: String::from("localhost"),
+                rest
+            }
+        );
+    }
+
+    #[test]
+    fn test_serde_enum_variants() {
+        #[derive(Deserialize, PartialEq, Debug)]
+        enum Backend {
+            Memory,
+            Redis(String),
+            Memcache(String, u16),
+            File { path: String },
+        }
+
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct MyStruct {
+            a: Backend,
+            b: Backend,
+            c: Backend,
+            d: Backend,
+            e: Backend,
+            pair: (String, u16),
+        }
+
+        let item = |key, values| ConfigItem {
+            key,
+            values,
+            children: vec![],
+        };
+
+        let path = || item("path", vec![ConfigValue::String("/tmp/cache")]);
+        let items = vec![
+            item(
+                "pair",
+                vec![ConfigValue::String("localhost"), ConfigValue::Number(80.0)],
+            ),
+            item("a", vec![ConfigValue::String("Memory")]),
+            item(
+                "b",
+                vec![
+                    ConfigValue::String("Redis"),
+                    ConfigValue::String("localhost:6379"),
+                ],
+            ),
+            item(
+                "c",
+                vec![
+                    ConfigValue::String("Memcache"),
+                    ConfigValue::String("localhost"),
+                    ConfigValue::Number(11211.0),
+                ],
+            ),
+            ConfigItem {
+                key: "d",
+                values: vec![ConfigValue::String("File")],
+                children: vec![path()],
+            },
+            ConfigItem {
+                key: "e",
+                values: vec![],
+                children: vec![ConfigItem {
+                    key: "File",
+                    values: vec![],
+                    children: vec![path()],
+                }],
+            },
+        ];
+
+        let actual: MyStruct = from_collectd(&items).unwrap();
+        let file = || Backend::File {
+            path: String::from("/tmp/cache"),
+        };
+        assert_eq!(
+            actual,
+            MyStruct {
+                a: Backend::Memory,
+                b: Backend::Redis(String::from("localhost:6379")),
+                c: Backend::Memcache(String::from("localhost"), 11211),
+                d: file(),
+                e: file(),
+                pair: (String::from("localhost"), 80),
+            }
+        );
+
+        let items = vec![item(
+            "a",
+            vec![ConfigValue::String("Memory"), ConfigValue::Number(1.0)],
+        )];
+
+        let err = from_collectd::<MyStruct>(&items).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "a: expecting no values after the unit variant `Memory`, found: 1"
+        );
+
+        let items = vec![item("a", vec![ConfigValue::Number(1.0)])];
+        let err = from_collectd::<MyStruct>(&items).unwrap_err();
+        assert_eq!(err.path(), "a");
+        assert!(matches!(*err.kind(), DeError::ExpectVariant));
+    }
+
+    #[test]
+    fn test_serde_seq_trailing_values() {
+        #[derive(Deserialize, PartialEq, Debug)]
+        #[serde(rename_all = "lowercase")]
+        enum Backend {
+            Tcp(String, u16),
+        }
+
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct MyStruct {
+            backend: Option<Backend>,
+            pair: Option<(String, u16)>,
+        }
+
+        let item = |key, values| ConfigItem {
+            key,
+            values,
+            children: vec![],
+        };
+
+        let items = vec![item(
+            "backend",
+            vec![
+                ConfigValue::String("tcp"),
+                ConfigValue::String("h"),
+                ConfigValue::Number(80.0),
+                ConfigValue::Number(1.0),
+            ],
+        )];
+
+        let err = from_collectd::<MyStruct>(&items).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "backend: error from deserialization: invalid length 3, expected 2 values, found: \"h\" 80 1"
+        );
+
+        let items = vec![item(
+            "pair",
+            vec![
+                ConfigValue::String("h"),
+                ConfigValue::Number(80.0),
+                ConfigValue::Number(1.0),
+            ],
+        )];
+
+        let err = from_collectd::<MyStruct>(&items).unwrap_err();
+        assert_eq!(err.path(), "pair");
+        assert!(err
+            .to_string()
+            .contains("invalid length 3, expected 2 values"));
+    }
+
+    #[test]
+    fn test_serde_internally_tagged_enum() {
+        #[derive(Deserialize, PartialEq, Debug)]
+        #[serde(tag = "Type")]
+        enum Auth {
+            None,
+            Basic {
+                #[serde(rename = "User")]
+                user: String,
+            },
+        }
+
+        #[derive(Deserialize, PartialEq, Debug)]
+        #[allow(non_snake_case)]
+        struct MyStruct {
+            Auth: Auth,
+        }
+
+        let items = vec![ConfigItem {
+            key: "Auth",
+            values: vec![],
+            children: vec![
+                ConfigItem {
+                    key: "Type",
+                    values: vec![ConfigValue::String("Basic")],
+                    children: vec![],
+                },
+                ConfigItem {
+                    key: "User",
+                    values: vec![ConfigValue::String("x")],
+                    children: vec![],
+                },
+            ],
+        }];
+
+        let actual: MyStruct = from_collectd(&items).unwrap();
+        assert_eq!(
+            actual,
+            MyStruct {
+                Auth: Auth::Basic {
+                    user: String::from("x")
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn test_serde_labeled() {
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct Database {
+            host: String,
+        }
+
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct MyStruct {
+            database: Vec<Labeled<Database>>,
+            cache: HashMap<String, Database>,
+            shard: Labeled<Database, (String, u16)>,
+        }
+
+        let block = |key, labels, host| ConfigItem {
+            key,
+            values: labels,
+            children: vec![ConfigItem {
+                key: "host",
+                values: vec![ConfigValue::String(host)],
+                children: vec![],
+            }],
+        };
+
+        let items = vec![
+            block("database", vec![ConfigValue::String("prod")], "db1"),
+            block("database", vec![ConfigValue::String("test")], "db2"),
+            block("cache", vec![ConfigValue::String("redis")], "cache1"),
+            block("cache", vec![ConfigValue::String("memcache")], "cache2"),
+            block(
+                "shard",
+                vec![ConfigValue::String("users"), ConfigValue::Number(2.0)],
+                "db3",
+            ),
+        ];
+
+        let actual: MyStruct = from_collectd(&items).unwrap();
+        let db = |host: &str| Database {
+            host: String::from(host),
+        };
+
+        let mut cache = HashMap::new();
+        cache.insert(String::from("redis"), db("cache1"));
+        cache.insert(String::from("memcache"), db("cache2"));
+        assert_eq!(
+            actual,
+            MyStruct {
+                database: vec![
+                    Labeled {
+                        label: String::from("prod"),
+                        value: db("db1"),
+                    },
+                    Labeled {
+                        label: String::from("test"),
+                        value: db("db2"),
+                    },
+                ],
+                cache,
+                shard: Labeled {
+                    label: (String::from("users"), 2),
+                    value: db("db3"),
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_serde_labeled_error_path() {
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct Database {
+            port: u16,
+        }
+
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct MyStruct {
+            database: HashMap<String, Database>,
+        }
+
+        let block = |label, port| ConfigItem {
+            key: "database",
+            values: vec![ConfigValue::String(label)],
+            children: vec![ConfigItem {
+                key: "port",
+                values: vec![port],
+                children: vec![],
+            }],
+        };
+
+        let items = vec![
+            block("prod", ConfigValue::Number(5432.0)),
+            block("test", ConfigValue::String("5432")),
+        ];
+
+        let err = from_collectd::<MyStruct>(&items).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "database[\"test\"].port: expecting number, found: \"5432\""
+        );
+    }
+
+    #[test]
+    fn test_serde_error_path() {
+        #[derive(Deserialize, PartialEq, Eq, Debug)]
+        #[allow(non_snake_case)]
+        struct Node {
+            Address: String,
+        }
+
+        #[derive(Deserialize, PartialEq, Eq, Debug)]
+        #[allow(non_snake_case)]
+        struct MyStruct {
+            Node: Vec<Node>,
+        }
+
+        let node = |value| ConfigItem {
+            key: "Node",
+            values: vec![],
+            children: vec![ConfigItem {
+                key: "Address",
+                values: vec![value],
+                children: vec![],
+            }],
+        };
+
+        let items = vec![
+            node(ConfigValue::String("localhost")),
+            node(ConfigValue::String("127.0.0.1")),
+            node(ConfigValue::Number(2003.0)),
+        ];
+
+        let err = from_collectd::<MyStruct>(&items).unwrap_err();
+        assert_eq!(err.path(), "Node[2].Address");
+        assert_eq!(err.value(), Some("2003"));
+        assert_eq!(
+            err.to_string(),
+            "Node[2].Address: expecting string, found: 2003"
+        );
+    }
+
+    #[test]
+    fn test_serde_error_without_values() {
+        #[derive(Deserialize, PartialEq, Eq, Debug)]
+        enum Backend {
+            File(String),
+        }
+
+        #[derive(Deserialize, PartialEq, Eq, Debug)]
+        struct MyStruct {
+            backend: Backend,
+        }
+
+        let items = vec![ConfigItem {
+            key: "backend",
+            values: vec![ConfigValue::String("File")],
+            children: vec![],
+        }];
+
+        let err = from_collectd::<MyStruct>(&items).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "backend: expecting values to contain a single entry"
+        );
+    }
+
+    #[test]
+    fn test_serde_error_single_value() {
+        #[derive(Deserialize, PartialEq, Eq, Debug)]
+        struct MyStruct {
+            host: String,
+        }
+
+        let items = vec![ConfigItem {
+            key: "host",
+            values: vec![ConfigValue::String("a"), ConfigValue::Boolean(true)],
+            children: vec![],
+        }];
+
+        let err = from_collectd::<MyStruct>(&items).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "host: expecting values to contain a single entry, found: \"a\" true"
+        );
+
+        let err = from_collectd::<MyStruct>(&[]).unwrap_err();
+        assert_eq!(err.path(), "");
+        assert_eq!(
+            err.to_string(),
+            "error from deserialization: missing field `host`"
+        );
+    }
 }
@@ -1,3 +1,5 @@
+use crate::api::{Value, ValueType};
+use chrono::Duration;
 use std::error;
 use std::fmt;
 use std::panic::PanicHookInfo;
@@ -69,6 +71,7 @@ impl error::Error for ArrayError {
 
 /// Error that occurred while receiving values from collectd to write
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum ReceiveError {
     /// A plugin submitted a field that contained invalid UTF-8 characters
     Utf8 {
@@ -91,6 +94,24 @@ pub enum ReceiveError {
         /// Stringly typed error message
         msg: &'static str,
     },
+
+    /// A plugin dispatched a notification with a severity outside the known range
+    Severity {
+        /// plugin where the error originates
+        plugin: String,
+
+        /// the unrecognized severity
+        severity: i32,
+    },
+
+    /// An identifier is not in the "host/plugin-instance/type-instance" format
+    Identifier {
+        /// the malformed identifier
+        identifier: String,
+
+        /// what is wrong with the identifier
+        msg: &'static str,
+    },
 }
 
 impl fmt::Display for ReceiveError {
@@ -110,6 +131,20 @@ impl fmt::Display for ReceiveError {
             } => {
                 write!(f, "plugin: {}, field: {}: {}", plugin, field, msg)
             }
+            ReceiveError::Severity {
+                ref plugin,
+                severity,
+            } => {
+                write!(
+                    f,
+                    "plugin: {} submitted unknown severity: {}",
+                    plugin, severity
+                )
+            }
+            ReceiveError::Identifier {
+                ref identifier,
+                msg,
+            } => write!(f, "identifier: {} is malformed: {}", identifier, msg),
         }
     }
 }
@@ -122,17 +157,165 @@ impl error::Error for ReceiveError {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match *self {
             ReceiveError::Utf8 { ref err, .. } => Some(err),
-            ReceiveError::Metadata { .. } => None,
+            ReceiveError::Metadata { .. }
+            | ReceiveError::Severity { .. }
+            | ReceiveError::Identifier { .. } => None,
+        }
+    }
+}
+
+/// Error that occurs when looking up a data set or when values don't conform to it
+#[derive(Debug, Clone)]
+pub enum DataSetError {
+    /// The type is not known to collectd (eg: it's missing from types.db)
+    UnknownType(String),
+
+    /// A data source name of the type contained invalid UTF-8 characters
+    Utf8 {
+        /// the type where the error originates
+        type_: String,
+
+        /// the inner utf-8 error
+        err: Utf8Error,
+    },
+
+    /// A data source of the type has a value type that isn't recognized
+    UnknownValueType {
+        /// the type where the error originates
+        type_: String,
+
+        /// the unrecognized value type
+        value_type: i32,
+    },
+
+    /// The number of values differs from the number of data sources
+    Length {
+        /// the type that the values were checked against
+        type_: String,
+
+        /// value types of the type's data sources
+        expected: Vec<ValueType>,
+
+        /// number of values received
+        received: usize,
+    },
+
+    /// A value is of a different kind than its data source
+    Mismatch {
+        /// the type that the values were checked against
+        type_: String,
+
+        /// name of the data source
+        source: String,
+
+        /// value type of the data source
+        expected: ValueType,
+
+        /// the offending value
+        value: Value,
+    },
+
+    /// A value is outside of the range accepted by its data source
+    OutOfRange {
+        /// the type that the values were checked against
+        type_: String,
+
+        /// name of the data source
+        source: String,
+
+        /// the offending value
+        value: Value,
+
+        /// minimum of the data source
+        min: f64,
+
+        /// maximum of the data source
+        max: f64,
+    },
+}
+
+impl fmt::Display for DataSetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            DataSetError::UnknownType(ref type_) => write!(f, "unknown type: {}", type_),
+            DataSetError::Utf8 { ref type_, .. } => {
+                write!(f, "type: {} has a data source that is not utf8", type_)
+            }
+            DataSetError::UnknownValueType {
+                ref type_,
+                value_type,
+            } => write!(f, "type: {} has unknown value type: {}", type_, value_type),
+            DataSetError::Length {
+                ref type_,
+                ref expected,
+                received,
+            } => {
+                write!(f, "type: {} expects {} values (", type_, expected.len())?;
+                for (i, value_type) in expected.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", value_type)?;
+                }
+                write!(f, ") but received {}", received)
+            }
+            DataSetError::Mismatch {
+                ref type_,
+                ref source,
+                expected,
+                value,
+            } => write!(
+                f,
+                "type: {}, data source: {} expects a {} but received {:?}",
+                type_, source, expected, value
+            ),
+            DataSetError::OutOfRange {
+                ref type_,
+                ref source,
+                value,
+                min,
+                max,
+            } => write!(
+                f,
+                "type: {}, data source: {} received {} which is outside of [{}, {}]",
+                type_, source, value, min, max
+            ),
+        }
+    }
+}
+
+impl error::Error for DataSetError {
+    fn description(&self) -> &str {
+        "error with data set"
+    }
+
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            DataSetError::Utf8 { ref err, .. } => Some(err),
+            _ => None,
         }
     }
 }
 
 /// Errors that occur when submitting values to collectd
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum SubmitError {
     /// Contains the exit status that collectd returns when a submission fails
     Dispatch(i32),
 
+    /// The submitted values do not conform to the data set of the type
+    DataSet(DataSetError),
+
+    /// More values were submitted at once than can be passed to collectd
+    TooManyValues {
+        /// The most values that can be submitted at once
+        max: usize,
+
+        /// The number of values that were submitted
+        received: usize,
+    },
+
     /// Error submitting a field
     Field {
         /// Name of field where error occurred
@@ -147,9 +330,15 @@ impl fmt::Display for SubmitError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
             SubmitError::Dispatch(code) => {
-                write!(f, "plugin_dispatch_values returned an error: {}", code)
+                write!(f, "dispatching to collectd returned an error: {}", code)
             }
+            SubmitError::TooManyValues { max, received } => write!(
+                f,
+                "at most {} values can be submitted at once, received: {}",
+                max, received
+            ),
             SubmitError::Field { name, .. } => write!(f, "error submitting {}", name),
+            SubmitError::DataSet(_) => write!(f, "values do not conform to the data set"),
         }
     }
 }
@@ -162,11 +351,43 @@ impl error::Error for SubmitError {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match *self {
             SubmitError::Dispatch(_code) => None,
+            SubmitError::TooManyValues { .. } => None,
             SubmitError::Field { ref err, .. } => Some(err),
+            SubmitError::DataSet(ref err) => Some(err),
         }
     }
 }
 
+/// Error that occurs when collectd fails to unregister a callback, most likely because the
+/// callback was never registered or has already been unregistered
+#[derive(Debug, Clone)]
+pub struct UnregisterError {
+    /// Name of the plugin (or read group) that was to be unregistered
+    pub name: String,
+
+    /// The kind of callback that was to be unregistered
+    pub callback: &'static str,
+
+    /// The exit status that collectd returned
+    pub code: i32,
+}
+
+impl fmt::Display for UnregisterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unable to unregister {} callback for {}: {}",
+            self.callback, self.name, self.code
+        )
+    }
+}
+
+impl error::Error for UnregisterError {
+    fn description(&self) -> &str {
+        "unable to unregister callback"
+    }
+}
+
 /// If a plugin advertises that it supports a certain functionality, but doesn't implement the
 /// necessary `Plugin` function, this error is returned.
 #[derive(Clone, Copy, Debug)]
@@ -184,6 +405,40 @@ impl error::Error for NotImplemented {
     }
 }
 
+/// Errors that occur when running an asynchronous plugin
+#[cfg(feature = "tokio")]
+#[derive(Clone, Debug, PartialEq)]
+pub enum RuntimeError {
+    /// The runtime was not started or has been shutdown
+    NotRunning,
+
+    /// The plugin did not complete within the given duration
+    Timeout(Duration),
+
+    /// The timeout is negative or too large to wait on
+    InvalidTimeout(Duration),
+}
+
+#[cfg(feature = "tokio")]
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            RuntimeError::NotRunning => write!(f, "tokio runtime is not running"),
+            RuntimeError::Timeout(d) => {
+                write!(f, "did not complete within {}ms", d.num_milliseconds())
+            }
+            RuntimeError::InvalidTimeout(d) => write!(f, "invalid timeout: {}", d),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl error::Error for RuntimeError {
+    fn description(&self) -> &str {
+        "error running asynchronous plugin"
+    }
+}
+
 /// Errors that occur when retrieving rates
 #[derive(Clone, Debug)]
 pub struct CacheRateError;
@@ -226,6 +481,12 @@ pub enum FfiError<'a> {
     /// Collectd gave us multiple configs to deserialize
     MultipleConfig,
 
+    /// Multiple plugins were registered under the same name
+    DuplicateInstance(String),
+
+    /// A plugin's read interval is not positive or is too large for collectd
+    InvalidInterval(String, Duration),
+
     /// Collectd gave us field that contains invalid UTF-8 characters
     Utf8(&'static str, Utf8Error),
 }
@@ -238,6 +499,16 @@ impl fmt::Display for FfiError<'_> {
                 write!(f, "unrecognized severity level: {}", severity)
             }
             FfiError::MultipleConfig => write!(f, "duplicate config section"),
+            FfiError::DuplicateInstance(ref name) => {
+                write!(f, "multiple plugins registered as: {}", name)
+            }
+            FfiError::InvalidInterval(ref name, interval) => {
+                write!(
+                    f,
+                    "plugin {} has an invalid read interval: {}",
+                    name, interval
+                )
+            }
             FfiError::Panic => write!(f, "plugin panicked"),
             FfiError::PanicHook(info) => {
                 write!(f, "plugin panicked: ")?;
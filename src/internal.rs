@@ -1,23 +1,46 @@
 //! Module used exclusively to setup the `collectd_plugin!` macro. No public functions from here
 //! should be used.
 use crate::api::{
-    empty_to_none, get_default_interval, log_err, CdTime, ConfigItem, LogLevel, ValueList,
+    empty_to_none, get_default_interval, log_err, CdTime, ConfigItem, Identifier, LogLevel,
+    Notification, ValueList,
 };
+use crate::api::{DataSet, OwnedConfigItem};
 use crate::bindings::{
-    cdtime_t, data_set_t, oconfig_item_t, plugin_register_complex_read, plugin_register_flush,
-    plugin_register_log, plugin_register_write, user_data_t, value_list_t,
+    cdtime_t, data_set_t, notification_t, oconfig_item_t, plugin_get_ds,
+    plugin_register_complex_read, plugin_register_flush, plugin_register_log,
+    plugin_register_missing, plugin_register_notification, plugin_register_write, user_data_t,
+    value_list_t, ARR_LENGTH,
 };
 use crate::errors::FfiError;
-use crate::plugins::{Plugin, PluginManager, PluginManagerCapabilities, PluginRegistration};
+use crate::plugins::{
+    ConfigBlocks, Plugin, PluginHandle, PluginManager, PluginManagerCapabilities,
+    PluginManagerName, PluginRegistration, SharedPluginManager,
+};
+use chrono::Duration;
+use std::collections::HashSet;
+use std::error;
 use std::ffi::{CStr, CString};
+use std::marker::PhantomData;
 use std::ops::Deref;
 use std::os::raw::{c_char, c_int, c_void};
-use std::panic::{self, catch_unwind};
+use std::panic::{self, catch_unwind, UnwindSafe};
 use std::ptr;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+
+/// The plugins that have been registered, so that they can be initialized and shutdown. Only weak
+/// references are kept so that a plugin is still dropped once all of its callbacks are unregistered.
+type RegisteredPlugins = Vec<(PluginHandle, Weak<Box<dyn Plugin>>)>;
+static PLUGINS: Mutex<RegisteredPlugins> = Mutex::new(Vec::new());
+
+/// The data sets that have been registered, so that exactly these are unregistered on shutdown
+static DATA_SETS: Mutex<Vec<DataSet>> = Mutex::new(Vec::new());
+
+/// Config blocks that are registered during init when multiple blocks are accepted
+static CONFIG_BLOCKS: Mutex<Vec<OwnedConfigItem>> = Mutex::new(Vec::new());
 
 extern "C" fn plugin_read(dt: *mut user_data_t) -> c_int {
-    let plugin = unsafe { &mut *((*dt).data as *mut Box<dyn Plugin>) };
+    let plugin = unsafe { &*((*dt).data as *const Box<dyn Plugin>) };
     let res = catch_unwind(|| plugin.read_values())
         .map_err(|_| FfiError::Panic)
         .and_then(|x| x.map_err(FfiError::Plugin));
@@ -30,7 +53,7 @@ extern "C" fn plugin_read(dt: *mut user_data_t) -> c_int {
 }
 
 extern "C" fn plugin_log(severity: c_int, message: *const c_char, dt: *mut user_data_t) {
-    let plugin = unsafe { &mut *((*dt).data as *mut Box<dyn Plugin>) };
+    let plugin = unsafe { &*((*dt).data as *const Box<dyn Plugin>) };
 
     // Guard against potential null messages even if they are not supposed to happen.
     if message.is_null() {
@@ -59,7 +82,7 @@ extern "C" fn plugin_write(
     vl: *const value_list_t,
     dt: *mut user_data_t,
 ) -> c_int {
-    let plugin = unsafe { &mut *((*dt).data as *mut Box<dyn Plugin>) };
+    let plugin = unsafe { &*((*dt).data as *const Box<dyn Plugin>) };
     let res = unsafe { ValueList::from(&*ds, &*vl) }
         .map_err(|e| FfiError::Collectd(Box::new(e)))
         .and_then(|list| {
@@ -80,7 +103,7 @@ extern "C" fn plugin_flush(
     identifier: *const c_char,
     dt: *mut user_data_t,
 ) -> c_int {
-    let plugin = unsafe { &mut *((*dt).data as *mut Box<dyn crate::Plugin>) };
+    let plugin = unsafe { &*((*dt).data as *const Box<dyn Plugin>) };
 
     let dur = if timeout == 0 {
         None
@@ -95,10 +118,15 @@ extern "C" fn plugin_flush(
             .to_str()
             .map(empty_to_none)
             .map_err(|e| FfiError::Utf8("flush identifier", e))
+            .and_then(|id| {
+                id.map(str::parse::<Identifier>)
+                    .transpose()
+                    .map_err(|e| FfiError::Collectd(Box::new(e)))
+            })
     };
 
     let res = ident.and_then(|id| {
-        catch_unwind(|| plugin.flush(dur, id))
+        catch_unwind(|| plugin.flush(dur, id.as_ref()))
             .map_err(|_| FfiError::Panic)
             .and_then(|x| x.map_err(FfiError::Plugin))
     });
@@ -110,114 +138,325 @@ extern "C" fn plugin_flush(
     res.map(|_| 0).unwrap_or(-1)
 }
 
+extern "C" fn plugin_notification(notif: *const notification_t, dt: *mut user_data_t) -> c_int {
+    let plugin = unsafe { &*((*dt).data as *const Box<dyn Plugin>) };
+    let res = unsafe { Notification::from(&*notif) }
+        .map_err(|e| FfiError::Collectd(Box::new(e)))
+        .and_then(|notif| {
+            catch_unwind(|| plugin.notification(notif))
+                .map_err(|_| FfiError::Panic)
+                .and_then(|x| x.map_err(FfiError::Plugin))
+        });
+
+    if let Err(ref e) = res {
+        log_err("notification", e);
+    }
+
+    res.map(|_| 0).unwrap_or(-1)
+}
+
+extern "C" fn plugin_missing(vl: *const value_list_t, dt: *mut user_data_t) -> c_int {
+    let plugin = unsafe { &*((*dt).data as *const Box<dyn Plugin>) };
+    let list = unsafe { &*vl };
+
+    // Collectd doesn't hand us the data set of a missing value list, and often the list doesn't
+    // contain values, so we only lookup the data set when there are values to interpret.
+    let empty = data_set_t {
+        type_: [0; ARR_LENGTH],
+        ds_num: 0,
+        ds: ptr::null_mut(),
+    };
+
+    let ds = if !list.values.is_null() && list.values_len > 0 {
+        unsafe { plugin_get_ds(list.type_.as_ptr()).as_ref() }.unwrap_or(&empty)
+    } else {
+        &empty
+    };
+
+    // Any plugin can dispatch a value list as missing, even one without a time or interval,
+    // which the conversion asserts on
+    let res = catch_unwind(|| ValueList::from(ds, list))
+        .map_err(|_| FfiError::Panic)
+        .and_then(|x| x.map_err(|e| FfiError::Collectd(Box::new(e))))
+        .and_then(|list| {
+            catch_unwind(|| plugin.missing(list))
+                .map_err(|_| FfiError::Panic)
+                .and_then(|x| x.map_err(FfiError::Plugin))
+        });
+
+    if let Err(ref e) = res {
+        log_err("missing", e);
+    }
+
+    res.map(|_| 0).unwrap_or(-1)
+}
+
 unsafe extern "C" fn plugin_free_user_data(raw: *mut c_void) {
-    let ptr = raw as *mut Box<dyn Plugin>;
-    drop(Box::from_raw(ptr));
+    let ptr = raw as *const Box<dyn Plugin>;
+    drop(Arc::from_raw(ptr));
+}
+
+/// The interval that collectd reads the plugin at. An interval that is not positive, or too
+/// large to be represented as a `cdtime_t`, is rejected.
+fn read_interval(handle: &PluginHandle, pl: &dyn Plugin) -> Result<cdtime_t, FfiError<'static>> {
+    match pl.read_interval() {
+        None => Ok(get_default_interval()),
+        Some(x) if x > Duration::zero() && x.num_nanoseconds().is_some() => {
+            Ok(CdTime::from(x).into())
+        }
+        Some(x) => Err(FfiError::InvalidInterval(String::from(handle.name()), x)),
+    }
 }
 
-fn plugin_registration(name: &str, plugin: Box<dyn Plugin>) {
-    let pl: Box<Box<dyn Plugin>> = Box::new(plugin);
+fn plugin_registration(
+    group: &str,
+    handle: &PluginHandle,
+    plugin: Box<dyn Plugin>,
+    interval: cdtime_t,
+) {
+    let pl: Arc<Box<dyn Plugin>> = Arc::new(plugin);
 
     // Grab all the properties we need until `into_raw` away
     let should_read = pl.capabilities().has_read();
     let should_log = pl.capabilities().has_log();
     let should_write = pl.capabilities().has_write();
     let should_flush = pl.capabilities().has_flush();
-
-    let s = CString::new(name).expect("Plugin name to not contain nulls");
+    let should_notify = pl.capabilities().has_notification();
+    let should_miss = pl.capabilities().has_missing();
+
+    let s = CString::new(handle.name()).expect("Plugin name to not contain nulls");
+    let g = CString::new(group).expect("Plugin group to not contain nulls");
+
+    // The user data that is passed to read, writes, logs, etc. It is not passed to config or
+    // init. Each registered callback receives its own reference to the plugin, which collectd
+    // releases through the free function when the callback is unregistered. This way a plugin
+    // is only dropped once all of its callbacks have been unregistered, no matter the order:
+    // https://collectd.org/wiki/index.php/User_data_t
+    let user_data = || user_data_t {
+        data: Arc::into_raw(Arc::clone(&pl)) as *mut c_void,
+        free_func: Some(plugin_free_user_data),
+    };
 
     unsafe {
-        let plugin_ptr = Box::into_raw(pl) as *mut c_void;
-
-        // The user data that is passed to read, writes, logs, etc. It is not passed to
-        // config or init. Since user_data_t implements copy, we don't need to forget about
-        // it. See clippy suggestion (forget_copy)
-        let mut data = user_data_t {
-            data: plugin_ptr,
-            free_func: Some(plugin_free_user_data),
-        };
-
-        // If a plugin registers more than one callback, we make sure to deregister the
-        // free function to avoid data being freed twice:
-        // https://collectd.org/wiki/index.php/User_data_t
-        let mut no_free_data = user_data_t {
-            data: plugin_ptr,
-            free_func: None,
-        };
-
         if should_read {
             plugin_register_complex_read(
-                ptr::null(),
+                g.as_ptr(),
                 s.as_ptr(),
                 Some(plugin_read),
-                get_default_interval(),
-                &data,
+                interval,
+                &user_data(),
             );
         }
 
         if should_write {
-            let d = if !should_read {
-                &mut data
-            } else {
-                &mut no_free_data
-            };
-
-            plugin_register_write(s.as_ptr(), Some(plugin_write), d);
+            plugin_register_write(s.as_ptr(), Some(plugin_write), &user_data());
         }
 
         if should_log {
-            let d = if !should_read && !should_write {
-                &mut data
-            } else {
-                &mut no_free_data
-            };
-
-            plugin_register_log(s.as_ptr(), Some(plugin_log), d);
+            plugin_register_log(s.as_ptr(), Some(plugin_log), &user_data());
         }
 
         if should_flush {
-            let d = if !should_read && !should_write && !should_log {
-                &mut data
-            } else {
-                &mut no_free_data
-            };
+            plugin_register_flush(s.as_ptr(), Some(plugin_flush), &user_data());
+        }
+
+        if should_notify {
+            plugin_register_notification(s.as_ptr(), Some(plugin_notification), &user_data());
+        }
 
-            plugin_register_flush(s.as_ptr(), Some(plugin_flush), d);
+        if should_miss {
+            plugin_register_missing(s.as_ptr(), Some(plugin_missing), &user_data());
         }
     }
+
+    PLUGINS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push((handle.clone(), Arc::downgrade(&pl)));
 }
 
-fn register_all_plugins<T: PluginManager>(config: Option<&[ConfigItem<'_>]>) -> c_int {
-    let res = catch_unwind(|| T::plugins(config))
-        .map_err(|_| FfiError::Panic)
-        .and_then(|reged| reged.map_err(FfiError::Plugin))
-        .map(|registration| match registration {
-            PluginRegistration::Single(pl) => plugin_registration(T::name(), pl),
-            PluginRegistration::Multiple(v) => {
-                for (id, pl) in v {
-                    let name = format!("{}/{}", T::name(), id);
-
-                    plugin_registration(name.as_str(), pl)
+/// Plugins that are still registered (ie: have not been dropped)
+fn registered_plugins() -> Vec<(PluginHandle, Arc<Box<dyn Plugin>>)> {
+    PLUGINS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .filter_map(|(handle, pl)| pl.upgrade().map(|pl| (handle.clone(), pl)))
+        .collect()
+}
+
+/// Initializes each registered plugin. A plugin that fails to initialize has its callbacks
+/// unregistered and is forgotten so that it isn't shutdown, but this does not affect the other
+/// plugins.
+fn init_plugins() {
+    let mut failed = Vec::new();
+    for (handle, pl) in registered_plugins() {
+        let res = catch_unwind(|| pl.init())
+            .map_err(|_| FfiError::Panic)
+            .and_then(|x| x.map_err(FfiError::Plugin));
+
+        if let Err(ref e) = res {
+            log_err(&format!("{} init", handle.name()), e);
+
+            if let Err(e) = handle.unregister(pl.capabilities()) {
+                log_err("unregister", &FfiError::Collectd(Box::new(e)));
+            }
+
+            failed.push(Arc::downgrade(&pl));
+        }
+    }
+
+    PLUGINS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .retain(|(_, pl)| !failed.iter().any(|x| Weak::ptr_eq(x, pl)));
+}
+
+fn shutdown_plugins() -> c_int {
+    let plugins = registered_plugins();
+    PLUGINS.lock().unwrap_or_else(|e| e.into_inner()).clear();
+
+    let mut result = 0;
+    for (handle, pl) in plugins {
+        let res = catch_unwind(|| pl.shutdown())
+            .map_err(|_| FfiError::Panic)
+            .and_then(|x| x.map_err(FfiError::Plugin));
+
+        if let Err(ref e) = res {
+            result = -1;
+            log_err(&format!("{} shutdown", handle.name()), e);
+        }
+    }
+
+    result
+}
+
+fn register_all_plugins<M: SharedPluginManager>(
+    manager: &Arc<M>,
+    configs: &[Option<&[ConfigItem<'_>]>],
+) -> Result<(), FfiError<'static>> {
+    configs
+        .iter()
+        .map(|&config| {
+            catch_unwind(|| Arc::clone(manager).plugins(config))
+                .map_err(|_| FfiError::Panic)
+                .and_then(|reged| reged.map_err(FfiError::Plugin))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(|registrations| {
+            registrations
+                .into_iter()
+                .flat_map(|registration| match registration {
+                    PluginRegistration::Single(pl) => vec![(PluginHandle::single::<M>(), pl)],
+                    PluginRegistration::Multiple(v) => v
+                        .into_iter()
+                        .map(|(id, pl)| (PluginHandle::instance::<M>(&id), pl))
+                        .collect(),
+                })
+                .collect::<Vec<_>>()
+        })
+        .and_then(|plugins| {
+            // Nothing is registered unless every plugin can be registered under its own name
+            // and at a valid interval
+            let mut names = HashSet::new();
+            let mut intervals = Vec::with_capacity(plugins.len());
+            for (handle, pl) in &plugins {
+                if !names.insert(handle.name()) {
+                    return Err(FfiError::DuplicateInstance(String::from(handle.name())));
                 }
+
+                intervals.push(read_interval(handle, pl.as_ref())?);
             }
+
+            for ((handle, pl), interval) in plugins.into_iter().zip(intervals) {
+                plugin_registration(M::manager_name(), &handle, pl, interval);
+            }
+
+            Ok(())
+        })
+}
+
+/// Registers the plugins from the config blocks that were collected when multiple blocks are
+/// accepted
+fn register_config_blocks<M: SharedPluginManager>(
+    manager: &Arc<M>,
+) -> Result<(), FfiError<'static>> {
+    let blocks = std::mem::take(&mut *CONFIG_BLOCKS.lock().unwrap_or_else(|e| e.into_inner()));
+    let blocks: Vec<ConfigItem<'_>> = blocks.iter().map(OwnedConfigItem::as_item).collect();
+
+    match manager.config_blocks() {
+        ConfigBlocks::Reject => Ok(()),
+        ConfigBlocks::Merge => {
+            let merged: Vec<ConfigItem<'_>> = blocks
+                .into_iter()
+                .flat_map(|block| block.children)
+                .collect();
+            register_all_plugins(manager, &[Some(&merged)])
+        }
+        ConfigBlocks::Separate => {
+            let configs: Vec<_> = blocks
+                .iter()
+                .map(|block| Some(block.children.as_slice()))
+                .collect();
+            register_all_plugins(manager, &configs)
+        }
+    }
+}
+
+fn register_data_sets<M: SharedPluginManager>(manager: &Arc<M>) -> c_int {
+    let res = catch_unwind(|| manager.data_sets())
+        .map_err(|_| FfiError::Panic)
+        .and_then(|sets| {
+            let mut registered = DATA_SETS.lock().unwrap_or_else(|e| e.into_inner());
+            for set in sets {
+                set.register()
+                    .map_err(|e| FfiError::Collectd(Box::new(e)))?;
+                registered.push(set);
+            }
+
+            Ok(())
         });
 
     if let Err(ref e) = res {
-        log_err("collectd config", e);
+        log_err("data set registration", e);
+    }
+    res.map(|_| 0).unwrap_or(-1)
+}
+
+/// Unregisters the data sets that were registered during init. All data sets are attempted
+/// even if one fails.
+fn unregister_data_sets() -> c_int {
+    let sets = std::mem::take(&mut *DATA_SETS.lock().unwrap_or_else(|e| e.into_inner()));
+    let mut res = Ok(());
+    for set in &sets {
+        res = res.and(set.unregister());
+    }
+
+    let res = res.map_err(|e| FfiError::Collectd(Box::new(e)));
+
+    if let Err(ref e) = res {
+        log_err("data set unregistration", e);
     }
     res.map(|_| 0).unwrap_or(-1)
 }
 
-pub fn plugin_init<T: PluginManager>(config_seen: &AtomicBool) -> c_int {
-    let mut result = if !config_seen.swap(true, Ordering::SeqCst) {
-        register_all_plugins::<T>(None)
+pub fn plugin_init<M: SharedPluginManager>(manager: &Arc<M>, config_seen: &AtomicBool) -> c_int {
+    let mut result = register_data_sets(manager);
+
+    let registered = if !config_seen.swap(true, Ordering::SeqCst) {
+        register_all_plugins(manager, &[None])
     } else {
-        0
+        register_config_blocks(manager)
     };
 
-    let capabilities = T::capabilities();
+    if let Err(ref e) = registered {
+        result = -1;
+        log_err("collectd config", e);
+    }
+
+    let capabilities = manager.capabilities();
     if capabilities.intersects(PluginManagerCapabilities::INIT) {
-        let res = catch_unwind(T::initialize)
+        let res = catch_unwind(|| manager.initialize())
             .map_err(|_e| FfiError::Panic)
             .and_then(|init| init.map_err(FfiError::Plugin));
 
@@ -227,15 +466,21 @@ pub fn plugin_init<T: PluginManager>(config_seen: &AtomicBool) -> c_int {
         }
     }
 
+    init_plugins();
+
     result
 }
 
-pub fn plugin_shutdown<T: PluginManager>() -> c_int {
-    let mut result = 0;
+pub fn plugin_shutdown<M: SharedPluginManager>(manager: &Arc<M>) -> c_int {
+    let mut result = unregister_data_sets();
 
-    let capabilities = T::capabilities();
+    if shutdown_plugins() != 0 {
+        result = -1;
+    }
+
+    let capabilities = manager.capabilities();
     if capabilities.intersects(PluginManagerCapabilities::INIT) {
-        let res = catch_unwind(T::shutdown)
+        let res = catch_unwind(|| manager.shutdown())
             .map_err(|_e| FfiError::Panic)
             .and_then(|r| r.map_err(FfiError::Plugin));
 
@@ -245,31 +490,140 @@ pub fn plugin_shutdown<T: PluginManager>() -> c_int {
         }
     }
 
+    #[cfg(feature = "tokio")]
+    crate::runtime::shutdown();
+
+    // The plugin has had the chance to signal its threads to stop, so wait for them to finish
+    crate::thread::join_all();
+
     result
 }
 
 /// # Safety
 ///
 /// It is assumed that the config is not a null pointer
-pub unsafe fn plugin_complex_config<T: PluginManager>(
+pub unsafe fn plugin_complex_config<M: SharedPluginManager>(
+    manager: &Arc<M>,
     config_seen: &AtomicBool,
     config: *mut oconfig_item_t,
 ) -> c_int {
-    // If we've already seen the config, let's error out as one shouldn't use multiple
-    // sections of configuration (group them under nodes like write_graphite)
-    if config_seen.swap(true, Ordering::SeqCst) {
-        log_err("config", &FfiError::MultipleConfig);
-        return -1;
-    }
-
-    match ConfigItem::from(&*config) {
-        Ok(config) => register_all_plugins::<T>(Some(&config.children)),
+    let first = !config_seen.swap(true, Ordering::SeqCst);
+    let config = match ConfigItem::from(&*config) {
+        Ok(config) => config,
         Err(e) => {
             log_err(
                 "collectd config conversion",
                 &FfiError::Collectd(Box::new(e)),
             );
-            -1
+            return -1;
+        }
+    };
+
+    let res = config_block(manager, first, &config);
+    if let Err(ref e) = res {
+        log_err("collectd config", e);
+    }
+    res.map(|_| 0).unwrap_or(-1)
+}
+
+/// Registers the plugins of a config block, or keeps the block until init when multiple blocks
+/// are accepted
+fn config_block<M: SharedPluginManager>(
+    manager: &Arc<M>,
+    first: bool,
+    config: &ConfigItem<'_>,
+) -> Result<(), FfiError<'static>> {
+    match manager.config_blocks() {
+        // If we've already seen the config, let's error out as one shouldn't use multiple
+        // sections of configuration (group them under nodes like write_graphite)
+        ConfigBlocks::Reject if !first => Err(FfiError::MultipleConfig),
+        ConfigBlocks::Reject => register_all_plugins(manager, &[Some(&config.children)]),
+
+        // Collectd frees the config after it has been read, so a copy is kept until init, when
+        // all blocks have been seen
+        ConfigBlocks::Merge | ConfigBlocks::Separate => {
+            CONFIG_BLOCKS
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(OwnedConfigItem::from(config));
+            Ok(())
+        }
+    }
+}
+
+/// Allows a `PluginManager`, which consists of associated functions, to be used where a
+/// `SharedPluginManager` is expected
+pub struct PluginManagerAdapter<T> {
+    manager: PhantomData<fn() -> T>,
+}
+
+impl<T: PluginManager> PluginManagerAdapter<T> {
+    pub fn new() -> Self {
+        PluginManagerAdapter {
+            manager: PhantomData,
+        }
+    }
+}
+
+impl<T: PluginManager> Default for PluginManagerAdapter<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PluginManager> PluginManagerName for PluginManagerAdapter<T> {
+    fn manager_name() -> &'static str {
+        T::name()
+    }
+}
+
+impl<T: PluginManager> SharedPluginManager for PluginManagerAdapter<T> {
+    fn capabilities(&self) -> PluginManagerCapabilities {
+        T::capabilities()
+    }
+
+    fn data_sets(&self) -> Vec<DataSet> {
+        T::data_sets()
+    }
+
+    fn config_blocks(&self) -> ConfigBlocks {
+        T::config_blocks()
+    }
+
+    fn plugins(
+        self: Arc<Self>,
+        config: Option<&[ConfigItem<'_>]>,
+    ) -> Result<PluginRegistration, Box<dyn error::Error>> {
+        T::plugins(config)
+    }
+
+    fn initialize(&self) -> Result<(), Box<dyn error::Error>> {
+        T::initialize()
+    }
+
+    fn shutdown(&self) -> Result<(), Box<dyn error::Error>> {
+        T::shutdown()
+    }
+}
+
+/// Constructs the plugin manager when collectd loads the plugin, unless it already has been.
+/// Returns false if the constructor panicked, in which case no callbacks should be registered.
+pub fn construct_manager<M, F>(manager: &OnceLock<Arc<M>>, ctor: F) -> bool
+where
+    F: FnOnce() -> M + UnwindSafe,
+{
+    if manager.get().is_some() {
+        return true;
+    }
+
+    match catch_unwind(ctor) {
+        Ok(constructed) => {
+            let _ = manager.set(Arc::new(constructed));
+            true
+        }
+        Err(_) => {
+            log_err("module_register", &FfiError::Panic);
+            false
         }
     }
 }
@@ -279,3 +633,274 @@ pub fn register_panic_handler() {
         log_err("panic hook", &FfiError::PanicHook(info));
     }));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::ConfigValue;
+    use crate::plugins::PluginCapabilities;
+    use std::sync::atomic::AtomicUsize;
+
+    struct TestManager;
+
+    impl PluginManager for TestManager {
+        fn name() -> &'static str {
+            "test"
+        }
+
+        fn plugins(
+            _config: Option<&[ConfigItem<'_>]>,
+        ) -> Result<PluginRegistration, Box<dyn error::Error>> {
+            Ok(PluginRegistration::Multiple(vec![]))
+        }
+
+        fn data_sets() -> Vec<DataSet> {
+            vec![DataSet::new("queue", vec![])]
+        }
+    }
+
+    struct IntervalPlugin(Option<Duration>);
+
+    impl Plugin for IntervalPlugin {
+        fn read_interval(&self) -> Option<Duration> {
+            self.0
+        }
+    }
+
+    struct LifecyclePlugin {
+        fail_init: bool,
+        shutdowns: Arc<Mutex<usize>>,
+    }
+
+    impl Plugin for LifecyclePlugin {
+        fn capabilities(&self) -> PluginCapabilities {
+            PluginCapabilities::READ
+        }
+
+        fn init(&self) -> Result<(), Box<dyn error::Error>> {
+            if self.fail_init {
+                Err("unable to connect".into())
+            } else {
+                Ok(())
+            }
+        }
+
+        fn shutdown(&self) -> Result<(), Box<dyn error::Error>> {
+            *self.shutdowns.lock().unwrap() += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_init_and_shutdown_plugins() {
+        let shutdowns = Arc::new(Mutex::new(0));
+        let plugin = |fail_init| -> Arc<Box<dyn Plugin>> {
+            Arc::new(Box::new(LifecyclePlugin {
+                fail_init,
+                shutdowns: Arc::clone(&shutdowns),
+            }))
+        };
+
+        // Collectd owns the plugins through the registered callbacks
+        let healthy = plugin(false);
+        let broken = plugin(true);
+        for (instance, pl) in &[("healthy", &healthy), ("broken", &broken)] {
+            PLUGINS.lock().unwrap().push((
+                PluginHandle::instance::<TestManager>(instance),
+                Arc::downgrade(pl),
+            ));
+        }
+
+        init_plugins();
+        let names: Vec<_> = registered_plugins()
+            .iter()
+            .map(|(handle, _)| handle.name().to_string())
+            .collect();
+        assert_eq!(names, vec![String::from("test/healthy")]);
+
+        assert_eq!(shutdown_plugins(), 0);
+        assert_eq!(*shutdowns.lock().unwrap(), 1);
+        assert!(registered_plugins().is_empty());
+    }
+
+    #[test]
+    fn test_plugin_manager_adapter() {
+        let manager = Arc::new(PluginManagerAdapter::<TestManager>::new());
+        assert_eq!(PluginManagerAdapter::<TestManager>::manager_name(), "test");
+        assert_eq!(
+            manager.capabilities().bits(),
+            TestManager::capabilities().bits()
+        );
+        assert_eq!(manager.config_blocks(), ConfigBlocks::Reject);
+        assert_eq!(manager.data_sets()[0].name, "queue");
+
+        match manager.plugins(None).unwrap() {
+            PluginRegistration::Multiple(plugins) => assert!(plugins.is_empty()),
+            PluginRegistration::Single(_) => panic!("expected multiple plugins"),
+        }
+    }
+
+    #[test]
+    fn test_construct_manager_once() {
+        static CONSTRUCTED: AtomicUsize = AtomicUsize::new(0);
+        fn ctor() -> PluginManagerAdapter<TestManager> {
+            CONSTRUCTED.fetch_add(1, Ordering::SeqCst);
+            PluginManagerAdapter::new()
+        }
+
+        let manager = OnceLock::new();
+        assert!(construct_manager(&manager, ctor));
+        assert!(construct_manager(&manager, ctor));
+        assert_eq!(CONSTRUCTED.load(Ordering::SeqCst), 1);
+        assert!(manager.get().is_some());
+    }
+
+    #[test]
+    fn test_construct_manager_panic() {
+        let manager: OnceLock<Arc<PluginManagerAdapter<TestManager>>> = OnceLock::new();
+        assert!(!construct_manager(&manager, || panic!("no config")));
+        assert!(manager.get().is_none());
+    }
+
+    struct NoopPlugin;
+
+    impl Plugin for NoopPlugin {}
+
+    /// Registers a plugin for each `Instance` in the config and remembers the instances of each
+    /// call to `plugins`
+    struct BlocksManager {
+        blocks: ConfigBlocks,
+        calls: Mutex<Vec<Vec<String>>>,
+    }
+
+    impl BlocksManager {
+        fn new(blocks: ConfigBlocks) -> Arc<Self> {
+            Arc::new(BlocksManager {
+                blocks,
+                calls: Mutex::new(Vec::new()),
+            })
+        }
+    }
+
+    impl PluginManagerName for BlocksManager {
+        fn manager_name() -> &'static str {
+            "blocks"
+        }
+    }
+
+    impl SharedPluginManager for BlocksManager {
+        fn config_blocks(&self) -> ConfigBlocks {
+            self.blocks
+        }
+
+        fn plugins(
+            self: Arc<Self>,
+            config: Option<&[ConfigItem<'_>]>,
+        ) -> Result<PluginRegistration, Box<dyn error::Error>> {
+            let instances: Vec<String> = config
+                .unwrap_or_default()
+                .iter()
+                .flat_map(|item| item.values.iter())
+                .map(|value| match *value {
+                    ConfigValue::String(x) => String::from(x),
+                    _ => panic!("expected a string instance"),
+                })
+                .collect();
+
+            self.calls.lock().unwrap().push(instances.clone());
+            let plugins = instances
+                .into_iter()
+                .map(|x| (x, Box::new(NoopPlugin) as Box<dyn Plugin>))
+                .collect();
+            Ok(PluginRegistration::Multiple(plugins))
+        }
+    }
+
+    fn block<'a>(instances: &[&'a str]) -> ConfigItem<'a> {
+        ConfigItem {
+            key: "Plugin",
+            values: vec![ConfigValue::String("blocks")],
+            children: instances
+                .iter()
+                .map(|&x| ConfigItem {
+                    key: "Instance",
+                    values: vec![ConfigValue::String(x)],
+                    children: vec![],
+                })
+                .collect(),
+        }
+    }
+
+    fn calls(manager: &BlocksManager) -> Vec<Vec<String>> {
+        manager.calls.lock().unwrap().clone()
+    }
+
+    // The modes share the config blocks that are kept until init, so they are tested in turn
+    #[test]
+    fn test_config_blocks() {
+        let manager = BlocksManager::new(ConfigBlocks::Reject);
+        assert!(config_block(&manager, true, &block(&["a", "b"])).is_ok());
+        match config_block(&manager, false, &block(&["c"])) {
+            Err(FfiError::MultipleConfig) => {}
+            x => panic!("expected multiple config error, got: {:?}", x),
+        }
+        assert_eq!(calls(&manager), vec![vec!["a", "b"]]);
+
+        let manager = BlocksManager::new(ConfigBlocks::Reject);
+        match config_block(&manager, true, &block(&["a", "a"])) {
+            Err(FfiError::DuplicateInstance(ref name)) => assert_eq!(name, "blocks/a"),
+            x => panic!("expected duplicate instance error, got: {:?}", x),
+        }
+
+        let manager = BlocksManager::new(ConfigBlocks::Merge);
+        assert!(config_block(&manager, true, &block(&["a"])).is_ok());
+        assert!(config_block(&manager, false, &block(&["b", "c"])).is_ok());
+        assert!(calls(&manager).is_empty());
+        assert!(register_config_blocks(&manager).is_ok());
+        assert_eq!(calls(&manager), vec![vec!["a", "b", "c"]]);
+
+        let manager = BlocksManager::new(ConfigBlocks::Separate);
+        assert!(config_block(&manager, true, &block(&["a"])).is_ok());
+        assert!(config_block(&manager, false, &block(&["b"])).is_ok());
+        assert!(register_config_blocks(&manager).is_ok());
+        assert_eq!(calls(&manager), vec![vec!["a"], vec!["b"]]);
+
+        // Plugin names must be unique across the separate blocks
+        let manager = BlocksManager::new(ConfigBlocks::Separate);
+        assert!(config_block(&manager, true, &block(&["a"])).is_ok());
+        assert!(config_block(&manager, false, &block(&["a"])).is_ok());
+        match register_config_blocks(&manager) {
+            Err(FfiError::DuplicateInstance(ref name)) => assert_eq!(name, "blocks/a"),
+            x => panic!("expected duplicate instance error, got: {:?}", x),
+        }
+        assert!(CONFIG_BLOCKS.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unregister_registered_data_sets() {
+        let manager = Arc::new(PluginManagerAdapter::<TestManager>::new());
+        assert_eq!(register_data_sets(&manager), 0);
+        assert_eq!(DATA_SETS.lock().unwrap()[0].name, "queue");
+
+        assert_eq!(unregister_data_sets(), 0);
+        assert!(DATA_SETS.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_read_interval() {
+        let handle = PluginHandle::instance::<TestManager>("node1");
+        let interval = |x| read_interval(&handle, &IntervalPlugin(x));
+
+        assert_eq!(interval(None).unwrap(), get_default_interval());
+        assert_eq!(interval(Some(Duration::seconds(5))).unwrap(), 5 << 30);
+
+        for &x in &[
+            Duration::zero(),
+            Duration::seconds(-5),
+            Duration::days(365 * 1000),
+        ] {
+            let err = interval(Some(x)).unwrap_err();
+            assert!(err.to_string().contains("invalid read interval"));
+        }
+    }
+}
@@ -11,6 +11,7 @@
 //! - Deployment: compile against collectd version and scp to server
 //! - Referenced Rust libraries are statically linked
 //! - Help writing thread safe plugins thanks to the Rust compiler
+//! - Asynchronous plugins on a managed [Tokio](https://tokio.rs) runtime with the `tokio` feature
 //!
 //! ## Usage
 //!
@@ -21,7 +22,7 @@
 //! collectd-plugin = "0.16.0"
 //! ```
 //!
-//! Rust 1.33 or later is needed to build.
+//! Rust 1.81 or later is needed to build.
 //!
 //! Works with any collectd version 5.4+, but all users will need to specify the collectd api
 //! version they want to target via the `COLLECTD_VERSION` environment variable (or rely on
@@ -104,14 +105,28 @@ mod api;
 mod errors;
 #[macro_use]
 mod plugins;
+mod buffered;
+pub mod thread;
+
+#[cfg(feature = "tokio")]
+pub mod runtime;
 
 pub use crate::api::{
-    collectd_log, CdTime, CollectdLogger, CollectdLoggerBuilder, ConfigItem, ConfigValue, LogLevel,
-    MetaValue, Value, ValueList, ValueListBuilder, ValueReport,
+    collectd_log, globals, CdTime, CollectdLogger, CollectdLoggerBuilder, ConfigItem, ConfigValue,
+    DataSet, DataSource, Globals, Identifier, LogLevel, MetaValue, MultiValueBuilder, Notification,
+    NotificationBuilder, NotificationSeverity, PreparedSubmitter, Value, ValueList,
+    ValueListBuilder, ValueListOwned, ValueReport, ValueReportOwned, ValueType,
+};
+pub use crate::errors::{
+    CacheRateError, ConfigError, DataSetError, ReceiveError, SubmitError, UnregisterError,
 };
-pub use crate::errors::{CacheRateError, ConfigError, ReceiveError, SubmitError};
+
+pub use crate::buffered::{BatchSink, BufferedWriter, DropPolicy};
+#[cfg(feature = "tokio")]
+pub use crate::errors::RuntimeError;
 pub use crate::plugins::{
-    Plugin, PluginCapabilities, PluginManager, PluginManagerCapabilities, PluginRegistration,
+    unregister_read_group, ConfigBlocks, Plugin, PluginCapabilities, PluginHandle, PluginManager,
+    PluginManagerCapabilities, PluginManagerName, PluginRegistration, SharedPluginManager,
 };
 
 #[cfg(doctest)]
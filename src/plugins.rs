@@ -1,9 +1,17 @@
-use crate::api::{ConfigItem, LogLevel, ValueList};
-use crate::errors::NotImplemented;
+use crate::api::{ConfigItem, DataSet, Identifier, LogLevel, Notification, ValueList};
+use crate::bindings::{
+    plugin_unregister_flush, plugin_unregister_log, plugin_unregister_missing,
+    plugin_unregister_notification, plugin_unregister_read, plugin_unregister_read_group,
+    plugin_unregister_write,
+};
+use crate::errors::{NotImplemented, UnregisterError};
 use bitflags::bitflags;
 use chrono::Duration;
 use std::error;
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
 use std::panic::{RefUnwindSafe, UnwindSafe};
+use std::sync::Arc;
 
 bitflags! {
     /// Bitflags of capabilities that a plugin advertises to collectd.
@@ -13,6 +21,8 @@ bitflags! {
         const LOG =    0b0000_0010;
         const WRITE =  0b0000_0100;
         const FLUSH =  0b0000_1000;
+        const NOTIFICATION = 0b0001_0000;
+        const MISSING = 0b0010_0000;
     }
 }
 
@@ -24,6 +34,22 @@ bitflags! {
     }
 }
 
+/// How a plugin manager handles collectd's configuration containing more than one block for the
+/// plugin (eg: when the configuration is split across `Include`d files)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConfigBlocks {
+    /// A second block is an error
+    #[default]
+    Reject,
+
+    /// The children of all blocks are concatenated and `plugins` is called once during init
+    Merge,
+
+    /// `plugins` is called once per block during init, so that each block registers its own
+    /// plugins. The names of the plugins must be unique across all blocks.
+    Separate,
+}
+
 /// How many instances of the plugin will be registered
 pub enum PluginRegistration {
     /// Our module will only register a single plugin
@@ -49,6 +75,151 @@ impl PluginCapabilities {
     pub fn has_flush(self) -> bool {
         self.intersects(PluginCapabilities::FLUSH)
     }
+
+    pub fn has_notification(self) -> bool {
+        self.intersects(PluginCapabilities::NOTIFICATION)
+    }
+
+    pub fn has_missing(self) -> bool {
+        self.intersects(PluginCapabilities::MISSING)
+    }
+}
+
+/// Refers to the callbacks that collectd has registered for a plugin. Plugins are registered
+/// under the name of the `PluginManager` (`PluginRegistration::Single`) or under
+/// "{manager}/{id}" (`PluginRegistration::Multiple`), so a plugin can create a handle to itself
+/// or a sibling and stop any of the callbacks at runtime. Once all of a plugin's callbacks are
+/// unregistered, the plugin is dropped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginHandle {
+    name: String,
+}
+
+impl PluginHandle {
+    /// Handle to the plugin registered through `PluginRegistration::Single`
+    pub fn single<T: PluginManagerName + ?Sized>() -> PluginHandle {
+        PluginHandle {
+            name: String::from(T::manager_name()),
+        }
+    }
+
+    /// Handle to the plugin registered with the given id through `PluginRegistration::Multiple`
+    pub fn instance<T: PluginManagerName + ?Sized>(id: &str) -> PluginHandle {
+        PluginHandle {
+            name: format!("{}/{}", T::manager_name(), id),
+        }
+    }
+
+    /// The name that the plugin's callbacks are registered under in collectd
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Stops collectd from calling `Plugin::read_values`
+    pub fn unregister_read(&self) -> Result<(), UnregisterError> {
+        self.unregister_callback("read", plugin_unregister_read)
+    }
+
+    /// Stops collectd from calling `Plugin::write_values`
+    pub fn unregister_write(&self) -> Result<(), UnregisterError> {
+        self.unregister_callback("write", plugin_unregister_write)
+    }
+
+    /// Stops collectd from calling `Plugin::log`
+    pub fn unregister_log(&self) -> Result<(), UnregisterError> {
+        self.unregister_callback("log", plugin_unregister_log)
+    }
+
+    /// Stops collectd from calling `Plugin::flush`
+    pub fn unregister_flush(&self) -> Result<(), UnregisterError> {
+        self.unregister_callback("flush", plugin_unregister_flush)
+    }
+
+    /// Stops collectd from calling `Plugin::notification`
+    pub fn unregister_notification(&self) -> Result<(), UnregisterError> {
+        self.unregister_callback("notification", plugin_unregister_notification)
+    }
+
+    /// Stops collectd from calling `Plugin::missing`
+    pub fn unregister_missing(&self) -> Result<(), UnregisterError> {
+        self.unregister_callback("missing", plugin_unregister_missing)
+    }
+
+    /// Unregisters every callback that the given capabilities (most likely the plugin's own
+    /// `Plugin::capabilities`) advertise. All callbacks are attempted even if one fails, and
+    /// the first error is returned.
+    pub fn unregister(&self, capabilities: PluginCapabilities) -> Result<(), UnregisterError> {
+        let mut result = Ok(());
+        if capabilities.has_read() {
+            result = result.and(self.unregister_read());
+        }
+
+        if capabilities.has_write() {
+            result = result.and(self.unregister_write());
+        }
+
+        if capabilities.has_log() {
+            result = result.and(self.unregister_log());
+        }
+
+        if capabilities.has_flush() {
+            result = result.and(self.unregister_flush());
+        }
+
+        if capabilities.has_notification() {
+            result = result.and(self.unregister_notification());
+        }
+
+        if capabilities.has_missing() {
+            result = result.and(self.unregister_missing());
+        }
+
+        result
+    }
+
+    fn unregister_callback(
+        &self,
+        callback: &'static str,
+        f: unsafe extern "C" fn(*const c_char) -> c_int,
+    ) -> Result<(), UnregisterError> {
+        let s = CString::new(self.name.as_str()).expect("Plugin name to not contain nulls");
+        match unsafe { f(s.as_ptr()) } {
+            0 => Ok(()),
+            code => Err(UnregisterError {
+                name: self.name.clone(),
+                callback,
+                code,
+            }),
+        }
+    }
+}
+
+/// Stops collectd from calling `Plugin::read_values` for every plugin registered by the
+/// `PluginManager` (or `SharedPluginManager`), as they are all registered in a read group named
+/// after the manager.
+pub fn unregister_read_group<T: PluginManagerName + ?Sized>() -> Result<(), UnregisterError> {
+    let s = CString::new(T::manager_name()).expect("Plugin name to not contain nulls");
+    match unsafe { plugin_unregister_read_group(s.as_ptr()) } {
+        0 => Ok(()),
+        code => Err(UnregisterError {
+            name: String::from(T::manager_name()),
+            callback: "read group",
+            code,
+        }),
+    }
+}
+
+/// The name that a plugin manager registers its plugins under. Every `PluginManager` has it
+/// through its `name`, while a `SharedPluginManager` implements it directly.
+pub trait PluginManagerName {
+    /// Name of the plugin. Must not contain null characters or panic.
+    fn manager_name() -> &'static str;
+}
+
+impl<T: PluginManager + ?Sized> PluginManagerName for T {
+    fn manager_name() -> &'static str {
+        T::name()
+    }
 }
 
 /// Defines the entry point for a collectd plugin. Based on collectd's configuration, a
@@ -62,6 +233,19 @@ pub trait PluginManager {
         PluginManagerCapabilities::default()
     }
 
+    /// Data sets (types) that the plugins submit values under but that aren't found in
+    /// collectd's types.db. They are registered during init, before any values are read, and
+    /// unregistered on shutdown. Must not panic.
+    fn data_sets() -> Vec<DataSet> {
+        Vec::new()
+    }
+
+    /// Whether more than one configuration block for the plugin is accepted. By default, a second
+    /// block is an error. Must not panic.
+    fn config_blocks() -> ConfigBlocks {
+        ConfigBlocks::default()
+    }
+
     /// Returns one or many instances of a plugin that is configured from collectd's configuration
     /// file. If parameter is `None`, a configuration section for the plugin was not found, so
     /// default values should be used.
@@ -75,12 +259,102 @@ pub trait PluginManager {
         Err(NotImplemented.into())
     }
 
-    /// Cleanup any resources or glodal data, allocated during initialize()
+    /// Cleanup any resources or glodal data, allocated during initialize(). Threads spawned with
+    /// `collectd_plugin::thread::spawn` should be signalled to stop here, as they are joined
+    /// afterwards.
     fn shutdown() -> Result<(), Box<dyn error::Error>> {
         Ok(())
     }
 }
 
+/// Like `PluginManager`, but constructed once when collectd loads the plugin (see the second form
+/// of `collectd_plugin!`) instead of consisting of associated functions. State that is shared
+/// between the manager and its plugins (eg: a connection pool) is stored in the manager, and each
+/// plugin can hold onto an `Arc` of it. Every `PluginManager` keeps working through an adapter.
+///
+/// ```
+/// use collectd_plugin::{
+///     collectd_plugin, ConfigItem, Plugin, PluginManagerName, PluginRegistration,
+///     SharedPluginManager,
+/// };
+/// use std::error;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::sync::Arc;
+///
+/// #[derive(Default)]
+/// struct MyManager {
+///     reads: AtomicUsize,
+/// }
+///
+/// struct MyPlugin {
+///     manager: Arc<MyManager>,
+/// }
+///
+/// impl PluginManagerName for MyManager {
+///     fn manager_name() -> &'static str {
+///         "myplugin"
+///     }
+/// }
+///
+/// impl SharedPluginManager for MyManager {
+///     fn plugins(
+///         self: Arc<Self>,
+///         _config: Option<&[ConfigItem<'_>]>,
+///     ) -> Result<PluginRegistration, Box<dyn error::Error>> {
+///         Ok(PluginRegistration::Single(Box::new(MyPlugin { manager: self })))
+///     }
+/// }
+///
+/// impl Plugin for MyPlugin {
+///     fn read_values(&self) -> Result<(), Box<dyn error::Error>> {
+///         self.manager.reads.fetch_add(1, Ordering::Relaxed);
+///         Ok(())
+///     }
+/// }
+///
+/// collectd_plugin!(MyManager, MyManager::default);
+/// ```
+pub trait SharedPluginManager: PluginManagerName + Send + Sync + RefUnwindSafe {
+    /// Defines the capabilities of the plugin manager. Must not panic.
+    fn capabilities(&self) -> PluginManagerCapabilities {
+        PluginManagerCapabilities::default()
+    }
+
+    /// Data sets (types) that the plugins submit values under but that aren't found in
+    /// collectd's types.db. See `PluginManager::data_sets`. Must not panic.
+    fn data_sets(&self) -> Vec<DataSet> {
+        Vec::new()
+    }
+
+    /// Whether more than one configuration block for the plugin is accepted. See
+    /// `PluginManager::config_blocks`. Must not panic.
+    fn config_blocks(&self) -> ConfigBlocks {
+        ConfigBlocks::default()
+    }
+
+    /// Returns one or many instances of a plugin that is configured from collectd's configuration
+    /// file. If parameter is `None`, a configuration section for the plugin was not found, so
+    /// default values should be used. Plugins that need the manager's state can hold onto
+    /// `self`.
+    fn plugins(
+        self: Arc<Self>,
+        _config: Option<&[ConfigItem<'_>]>,
+    ) -> Result<PluginRegistration, Box<dyn error::Error>>;
+
+    /// Initialize any socket, files, event loops, or any other resources that will be shared
+    /// between multiple plugin instances.
+    fn initialize(&self) -> Result<(), Box<dyn error::Error>> {
+        Err(NotImplemented.into())
+    }
+
+    /// Cleanup any resources allocated during initialize(). Threads spawned with
+    /// `collectd_plugin::thread::spawn` should be signalled to stop here, as they are joined
+    /// afterwards.
+    fn shutdown(&self) -> Result<(), Box<dyn error::Error>> {
+        Ok(())
+    }
+}
+
 /// An individual plugin that is capable of reporting values to collectd, receiving values from
 /// other plugins, or logging messages. A plugin must implement `Sync + Send` as collectd could be sending
 /// values to be written or logged concurrently. The Rust compiler will ensure that everything
@@ -98,6 +372,35 @@ pub trait Plugin: Send + Sync + UnwindSafe + RefUnwindSafe {
         Err(NotImplemented.into())
     }
 
+    /// Called once for each registered plugin during collectd's init phase, after
+    /// `PluginManager::initialize`. This is the place to open connections or other resources
+    /// that should not be acquired while parsing the configuration. An error unregisters only this
+    /// plugin's callbacks, the other plugins from the same `PluginManager` are unaffected.
+    fn init(&self) -> Result<(), Box<dyn error::Error>> {
+        Ok(())
+    }
+
+    /// Called once for each plugin that is still registered during collectd's shutdown phase,
+    /// before `PluginManager::shutdown`. Release the resources acquired in `init` here.
+    ///
+    /// The callbacks stay registered: collectd shuts down plugins in turn, so `log` and
+    /// `write_values` may still be called (eg: with messages logged by other plugins' shutdown)
+    /// after this returns and must not assume the resources are still available.
+    fn shutdown(&self) -> Result<(), Box<dyn error::Error>> {
+        Ok(())
+    }
+
+    /// The interval at which collectd calls `read_values` for this plugin. By default, `None` is
+    /// returned and the plugin is read at the `Interval` defined in the global config (or in the
+    /// `LoadPlugin` block). Returning a duration allows each plugin registered by a
+    /// `PluginManager` to be read at its own rate (eg: from an `Interval` key in each `<Node>`
+    /// block) so that cheap and expensive probes can be part of the same plugin family. Queried
+    /// once when the plugin is registered. An interval that is not positive fails the
+    /// registration of all the plugins from the `PluginManager`.
+    fn read_interval(&self) -> Option<Duration> {
+        None
+    }
+
     /// This function is called when collectd expects the plugin to report values, which will occur
     /// at the `Interval` defined in the global config (but can be overridden). Implementations
     /// that expect to report values need to have at least have a capability of `READ`. An error in
@@ -118,20 +421,54 @@ pub trait Plugin: Send + Sync + UnwindSafe + RefUnwindSafe {
     fn flush(
         &self,
         _timeout: Option<Duration>,
-        _identifier: Option<&str>,
+        _identifier: Option<&Identifier>,
     ) -> Result<(), Box<dyn error::Error>> {
         Err(NotImplemented.into())
     }
+
+    /// Collectd is dispatching a notification (eg: a threshold was crossed or a plugin raised an
+    /// event). Implementations that want to receive notifications need to have a capability of
+    /// `NOTIFICATION`.
+    fn notification(&self, _notif: Notification<'_>) -> Result<(), Box<dyn error::Error>> {
+        Err(NotImplemented.into())
+    }
+
+    /// Collectd hasn't received an update to a value list in `Timeout` intervals (or a plugin
+    /// dispatched the value list as missing), so the source is considered gone. Only the
+    /// identifier, time, and interval of the list are filled in, so `values` is often empty.
+    /// Implementations that want to be informed need to have a capability of `MISSING`.
+    fn missing(&self, _list: ValueList<'_>) -> Result<(), Box<dyn error::Error>> {
+        Err(NotImplemented.into())
+    }
 }
 
-/// Sets up all the ffi entry points that collectd expects when given a `PluginManager`.
+/// Sets up all the ffi entry points that collectd expects when given a `PluginManager`, or a
+/// `SharedPluginManager` and a function that constructs it (eg: `collectd_plugin!(MyManager,
+/// MyManager::new)`). The constructor is called once when collectd loads the plugin.
 #[macro_export]
 macro_rules! collectd_plugin {
     ($type:ty) => {
+        $crate::collectd_plugin!(
+            $crate::internal::PluginManagerAdapter<$type>,
+            $crate::internal::PluginManagerAdapter::<$type>::new
+        );
+    };
+
+    ($type:ty, $ctor:expr) => {
         // Let's us know if we've seen our config section before
         static CONFIG_SEEN: ::std::sync::atomic::AtomicBool =
             ::std::sync::atomic::AtomicBool::new(false);
 
+        // The plugin manager constructed when collectd loads the plugin
+        static MANAGER: ::std::sync::OnceLock<::std::sync::Arc<$type>> =
+            ::std::sync::OnceLock::new();
+
+        fn collectd_plugin_manager() -> &'static ::std::sync::Arc<$type> {
+            MANAGER
+                .get()
+                .expect("plugin manager to be constructed in module_register")
+        }
+
         // This is the main entry point that collectd looks for. Our plugin manager will register
         // callbacks for configuration related to our name. It also registers a callback for
         // initialization for when configuration is absent or a single plugin wants to hold global
@@ -145,7 +482,11 @@ macro_rules! collectd_plugin {
 
             $crate::internal::register_panic_handler();
 
-            let s = CString::new(<$type as $crate::PluginManager>::name())
+            if !$crate::internal::construct_manager(&MANAGER, $ctor) {
+                return;
+            }
+
+            let s = CString::new(<$type as $crate::PluginManagerName>::manager_name())
                 .expect("Plugin name to not contain nulls");
 
             unsafe {
@@ -158,17 +499,17 @@ macro_rules! collectd_plugin {
         }
 
         extern "C" fn collectd_plugin_init() -> ::std::os::raw::c_int {
-            $crate::internal::plugin_init::<$type>(&CONFIG_SEEN)
+            $crate::internal::plugin_init(collectd_plugin_manager(), &CONFIG_SEEN)
         }
 
         extern "C" fn collectd_plugin_shutdown() -> ::std::os::raw::c_int {
-            $crate::internal::plugin_shutdown::<$type>()
+            $crate::internal::plugin_shutdown(collectd_plugin_manager())
         }
 
         unsafe extern "C" fn collectd_plugin_complex_config(
             config: *mut $crate::bindings::oconfig_item_t,
         ) -> ::std::os::raw::c_int {
-            $crate::internal::plugin_complex_config::<$type>(&CONFIG_SEEN, config)
+            $crate::internal::plugin_complex_config(collectd_plugin_manager(), &CONFIG_SEEN, config)
         }
     };
 }
@@ -178,6 +519,7 @@ mod tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_plugin_capabilities() {
         let capabilities = PluginCapabilities::READ | PluginCapabilities::WRITE;
         assert_eq!(capabilities.has_read(), true);
@@ -187,4 +529,27 @@ mod tests {
         assert_eq!(capabilities.has_read(), true);
         assert_eq!(capabilities.has_write(), false);
     }
+
+    #[test]
+    fn test_plugin_handle_name() {
+        struct MyManager;
+
+        impl PluginManager for MyManager {
+            fn name() -> &'static str {
+                "myplugin"
+            }
+
+            fn plugins(
+                _config: Option<&[ConfigItem<'_>]>,
+            ) -> Result<PluginRegistration, Box<dyn error::Error>> {
+                Ok(PluginRegistration::Multiple(vec![]))
+            }
+        }
+
+        assert_eq!(PluginHandle::single::<MyManager>().name(), "myplugin");
+        assert_eq!(
+            PluginHandle::instance::<MyManager>("node1").name(),
+            "myplugin/node1"
+        );
+    }
 }
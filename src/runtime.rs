@@ -0,0 +1,268 @@
+//! Asynchronous plugins that run on a Tokio runtime managed by this crate.
+//!
+//! Collectd calls plugins from its own threads, so an `AsyncPlugin` is wrapped in an
+//! `AsyncPluginAdapter` that blocks the calling thread on the plugin's future. The futures are
+//! driven by a runtime that is started when the first adapter is initialized (or explicitly with
+//! `start` from `PluginManager::initialize`) and shutdown when the plugin is shutdown.
+//!
+//! ```
+//! use collectd_plugin::runtime::{AsyncPlugin, AsyncPluginAdapter};
+//! use collectd_plugin::{PluginCapabilities, PluginRegistration, Value, ValueListBuilder};
+//! use std::error;
+//!
+//! struct MyPlugin;
+//!
+//! impl AsyncPlugin for MyPlugin {
+//!     fn capabilities(&self) -> PluginCapabilities {
+//!         PluginCapabilities::READ
+//!     }
+//!
+//!     async fn read_values(&self) -> Result<(), Box<dyn error::Error>> {
+//!         let values = [Value::Gauge(15.0)];
+//!         ValueListBuilder::new("myplugin", "gauge")
+//!             .values(&values)
+//!             .submit()?;
+//!         Ok(())
+//!     }
+//! }
+//!
+//! fn registration() -> PluginRegistration {
+//!     PluginRegistration::Single(Box::new(AsyncPluginAdapter::new(MyPlugin)))
+//! }
+//! ```
+use crate::api::{CdTime, Identifier, ValueList};
+use crate::bindings::plugin_get_interval;
+use crate::errors::{NotImplemented, RuntimeError};
+use crate::plugins::{Plugin, PluginCapabilities};
+use chrono::Duration;
+use std::error;
+use std::future::Future;
+use std::io;
+use std::panic::{RefUnwindSafe, UnwindSafe};
+use std::sync::Mutex;
+use tokio::runtime::{Handle, Runtime};
+
+static RUNTIME: Mutex<Option<Runtime>> = Mutex::new(None);
+
+/// How long the runtime waits for spawned tasks to finish on shutdown
+const SHUTDOWN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Starts the runtime if it isn't already running and returns a handle to it. Call this from
+/// `PluginManager::initialize` to spawn tasks that are shared between plugins.
+pub fn start() -> io::Result<Handle> {
+    let mut runtime = RUNTIME.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(ref rt) = *runtime {
+        return Ok(rt.handle().clone());
+    }
+
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .thread_name("collectd-tokio")
+        .build()?;
+
+    let handle = rt.handle().clone();
+    *runtime = Some(rt);
+    Ok(handle)
+}
+
+/// A handle to the runtime, if it is running
+pub fn handle() -> Option<Handle> {
+    RUNTIME
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map(|rt| rt.handle().clone())
+}
+
+/// Shuts down the runtime, giving spawned tasks a moment to finish. Called on plugin shutdown.
+pub(crate) fn shutdown() {
+    let runtime = RUNTIME.lock().unwrap_or_else(|e| e.into_inner()).take();
+    if let Some(rt) = runtime {
+        rt.shutdown_timeout(SHUTDOWN_TIMEOUT);
+    }
+}
+
+/// The asynchronous equivalent of `Plugin`. See `Plugin` for the meaning of each function.
+pub trait AsyncPlugin: Send + Sync + UnwindSafe + RefUnwindSafe {
+    /// A plugin's capabilities. See `Plugin::capabilities`. Only `READ`, `WRITE`, and `FLUSH` have
+    /// asynchronous callbacks, any other capability is ignored.
+    fn capabilities(&self) -> PluginCapabilities {
+        PluginCapabilities::default()
+    }
+
+    /// Acquire resources once the runtime is running. See `Plugin::init`
+    fn init(&self) -> impl Future<Output = Result<(), Box<dyn error::Error>>> {
+        async { Ok(()) }
+    }
+
+    /// Release the resources acquired in `init` before the runtime is shutdown. See
+    /// `Plugin::shutdown`
+    fn shutdown(&self) -> impl Future<Output = Result<(), Box<dyn error::Error>>> {
+        async { Ok(()) }
+    }
+
+    /// The interval at which collectd reads the plugin. See `Plugin::read_interval`
+    fn read_interval(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Report values to collectd. See `Plugin::read_values`
+    fn read_values(&self) -> impl Future<Output = Result<(), Box<dyn error::Error>>> {
+        async { Err(NotImplemented.into()) }
+    }
+
+    /// Receive values reported to collectd. See `Plugin::write_values`
+    fn write_values(
+        &self,
+        _list: ValueList<'_>,
+    ) -> impl Future<Output = Result<(), Box<dyn error::Error>>> {
+        async { Err(NotImplemented.into()) }
+    }
+
+    /// Flush buffered values. See `Plugin::flush`
+    fn flush(
+        &self,
+        _timeout: Option<Duration>,
+        _identifier: Option<&Identifier>,
+    ) -> impl Future<Output = Result<(), Box<dyn error::Error>>> {
+        async { Err(NotImplemented.into()) }
+    }
+}
+
+/// Turns an `AsyncPlugin` into a `Plugin` that can be registered with collectd. Each callback
+/// blocks collectd's thread until the plugin's future completes.
+pub struct AsyncPluginAdapter<P> {
+    plugin: P,
+    read_timeout: Option<Duration>,
+}
+
+impl<P: AsyncPlugin> AsyncPluginAdapter<P> {
+    pub fn new(plugin: P) -> Self {
+        AsyncPluginAdapter {
+            plugin,
+            read_timeout: None,
+        }
+    }
+
+    /// The longest a read may block collectd's read thread. By default this is the interval of
+    /// the read callback, so that a slow read does not delay the next one. A read that takes
+    /// longer is cancelled and reported as an error.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    fn block_on<F, T>(&self, fut: F) -> Result<T, Box<dyn error::Error>>
+    where
+        F: Future<Output = Result<T, Box<dyn error::Error>>>,
+    {
+        let handle = handle().ok_or(RuntimeError::NotRunning)?;
+        handle.block_on(fut)
+    }
+}
+
+impl<P: AsyncPlugin> Plugin for AsyncPluginAdapter<P> {
+    fn capabilities(&self) -> PluginCapabilities {
+        self.plugin.capabilities()
+            & (PluginCapabilities::READ | PluginCapabilities::WRITE | PluginCapabilities::FLUSH)
+    }
+
+    fn init(&self) -> Result<(), Box<dyn error::Error>> {
+        start()?;
+        self.block_on(self.plugin.init())
+    }
+
+    fn shutdown(&self) -> Result<(), Box<dyn error::Error>> {
+        self.block_on(self.plugin.shutdown())
+    }
+
+    fn read_interval(&self) -> Option<Duration> {
+        self.plugin.read_interval()
+    }
+
+    fn read_values(&self) -> Result<(), Box<dyn error::Error>> {
+        let timeout = self
+            .read_timeout
+            .unwrap_or_else(|| CdTime::from(unsafe { plugin_get_interval() }).into());
+
+        let limit = timeout
+            .to_std()
+            .map_err(|_| RuntimeError::InvalidTimeout(timeout))?;
+
+        self.block_on(async {
+            tokio::time::timeout(limit, self.plugin.read_values())
+                .await
+                .map_err(|_| RuntimeError::Timeout(timeout))?
+        })
+    }
+
+    fn write_values(&self, list: ValueList<'_>) -> Result<(), Box<dyn error::Error>> {
+        self.block_on(self.plugin.write_values(list))
+    }
+
+    fn flush(
+        &self,
+        timeout: Option<Duration>,
+        identifier: Option<&Identifier>,
+    ) -> Result<(), Box<dyn error::Error>> {
+        self.block_on(self.plugin.flush(timeout, identifier))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[derive(Default)]
+    struct SlowPlugin {
+        initialized: AtomicBool,
+    }
+
+    impl AsyncPlugin for SlowPlugin {
+        fn capabilities(&self) -> PluginCapabilities {
+            PluginCapabilities::READ | PluginCapabilities::LOG | PluginCapabilities::MISSING
+        }
+
+        async fn init(&self) -> Result<(), Box<dyn error::Error>> {
+            tokio::task::yield_now().await;
+            self.initialized.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn shutdown(&self) -> Result<(), Box<dyn error::Error>> {
+            self.initialized.store(false, Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn read_values(&self) -> Result<(), Box<dyn error::Error>> {
+            tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+            Ok(())
+        }
+
+        async fn flush(
+            &self,
+            _timeout: Option<Duration>,
+            _identifier: Option<&Identifier>,
+        ) -> Result<(), Box<dyn error::Error>> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_async_plugin_adapter() {
+        let plugin =
+            AsyncPluginAdapter::new(SlowPlugin::default()).read_timeout(Duration::milliseconds(10));
+        assert!(plugin.capabilities() == PluginCapabilities::READ);
+
+        plugin.init().unwrap();
+        assert!(plugin.plugin.initialized.load(Ordering::SeqCst));
+        assert!(plugin.flush(None, None).is_ok());
+
+        let err = plugin.read_values().unwrap_err();
+        assert_eq!(err.to_string(), "did not complete within 10ms");
+
+        plugin.shutdown().unwrap();
+        assert!(!plugin.plugin.initialized.load(Ordering::SeqCst));
+    }
+}
@@ -1,5 +1,37 @@
-use super::Value;
+//! Serialization of the values that collectd reports to write plugins.
+//!
+//! Value lists are serialized in the same shape as collectd's write_http JSON format, so
+//! writers can forward them to services that already consume it:
+//!
+//! ```json
+//! {
+//!   "values": [1901474177],
+//!   "dstypes": ["counter"],
+//!   "dsnames": ["value"],
+//!   "time": 1280959128.0,
+//!   "interval": 10.0,
+//!   "host": "leeloo.octo.it",
+//!   "plugin": "cpu",
+//!   "plugin_instance": "0",
+//!   "type": "cpu",
+//!   "type_instance": "idle",
+//!   "meta": {}
+//! }
+//! ```
+//!
+//! Times and intervals are serialized as (fractional) seconds, missing instances as empty
+//! strings, and metadata values as their plain value. A `ValueListOwned` can be deserialized from
+//! the same shape.
+mod values;
+
+use super::{
+    CdTime, MetaValue, Value, ValueList, ValueListOwned, ValueReport, ValueReportOwned, ValueType,
+};
+use chrono::prelude::*;
+use chrono::Duration;
+use serde::ser::SerializeStruct;
 use serde::{Serialize, Serializer};
+use std::collections::HashMap;
 
 impl Serialize for Value {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -15,12 +47,166 @@ impl Serialize for Value {
     }
 }
 
+impl Serialize for MetaValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match *self {
+            MetaValue::String(ref x) => serializer.serialize_str(x),
+            MetaValue::SignedInt(x) => serializer.serialize_i64(x),
+            MetaValue::UnsignedInt(x) => serializer.serialize_u64(x),
+            MetaValue::Double(x) => serializer.serialize_f64(x),
+            MetaValue::Boolean(x) => serializer.serialize_bool(x),
+        }
+    }
+}
+
+impl Serialize for CdTime {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let CdTime(ns) = *self;
+        serializer.serialize_f64(ns as f64 / 1e9)
+    }
+}
+
+/// The name of the data source type in write_http's JSON
+fn ds_type_name(value: &Value) -> &'static str {
+    match value.value_type() {
+        ValueType::Counter => "counter",
+        ValueType::Gauge => "gauge",
+        ValueType::Derive => "derive",
+        ValueType::Absolute => "absolute",
+    }
+}
+
+fn serialize_report<S>(
+    serializer: S,
+    name: &str,
+    value: &Value,
+    min: f64,
+    max: f64,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let mut state = serializer.serialize_struct("ValueReport", 5)?;
+    state.serialize_field("dsname", name)?;
+    state.serialize_field("dstype", ds_type_name(value))?;
+    state.serialize_field("value", value)?;
+    state.serialize_field("min", &min)?;
+    state.serialize_field("max", &max)?;
+    state.end()
+}
+
+impl Serialize for ValueReport<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_report(serializer, self.name, &self.value, self.min, self.max)
+    }
+}
+
+impl Serialize for ValueReportOwned {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_report(serializer, &self.name, &self.value, self.min, self.max)
+    }
+}
+
+/// The fields of a value list that are shared between `ValueList` and `ValueListOwned`
+struct ListFields<'a> {
+    values: Vec<(&'a str, Value)>,
+    host: &'a str,
+    plugin: &'a str,
+    plugin_instance: Option<&'a str>,
+    type_: &'a str,
+    type_instance: Option<&'a str>,
+    time: DateTime<Utc>,
+    interval: Duration,
+    meta: &'a HashMap<String, MetaValue>,
+}
+
+impl Serialize for ListFields<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let values: Vec<Value> = self.values.iter().map(|&(_, value)| value).collect();
+        let dstypes: Vec<&str> = self.values.iter().map(|(_, x)| ds_type_name(x)).collect();
+        let dsnames: Vec<&str> = self.values.iter().map(|&(name, _)| name).collect();
+
+        let mut state = serializer.serialize_struct("ValueList", 11)?;
+        state.serialize_field("values", &values)?;
+        state.serialize_field("dstypes", &dstypes)?;
+        state.serialize_field("dsnames", &dsnames)?;
+        state.serialize_field("time", &CdTime::from(self.time))?;
+        state.serialize_field("interval", &CdTime::from(self.interval))?;
+        state.serialize_field("host", self.host)?;
+        state.serialize_field("plugin", self.plugin)?;
+        state.serialize_field("plugin_instance", self.plugin_instance.unwrap_or(""))?;
+        state.serialize_field("type", self.type_)?;
+        state.serialize_field("type_instance", self.type_instance.unwrap_or(""))?;
+        state.serialize_field("meta", self.meta)?;
+        state.end()
+    }
+}
+
+impl Serialize for ValueList<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        ListFields {
+            values: self.values.iter().map(|x| (x.name, x.value)).collect(),
+            host: self.host,
+            plugin: self.plugin,
+            plugin_instance: self.plugin_instance,
+            type_: self.type_,
+            type_instance: self.type_instance,
+            time: self.time,
+            interval: self.interval,
+            meta: &self.meta,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl Serialize for ValueListOwned {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        ListFields {
+            values: self
+                .values
+                .iter()
+                .map(|x| (x.name.as_str(), x.value))
+                .collect(),
+            host: &self.host,
+            plugin: &self.plugin,
+            plugin_instance: self.plugin_instance.as_deref(),
+            type_: &self.type_,
+            type_instance: self.type_instance.as_deref(),
+            time: self.time,
+            interval: self.interval,
+            meta: &self.meta,
+        }
+        .serialize(serializer)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serde_test;
 
     use self::serde_test::{assert_ser_tokens, Token};
-    use super::Value;
+    use super::*;
 
     #[test]
     fn test_ser_value_counter() {
@@ -41,4 +227,47 @@ mod tests {
     fn test_ser_value_absolute() {
         assert_ser_tokens(&Value::Absolute(15), &[Token::U64(15)]);
     }
+
+    #[test]
+    fn test_ser_meta_value() {
+        assert_ser_tokens(&MetaValue::String(String::from("a")), &[Token::Str("a")]);
+        assert_ser_tokens(&MetaValue::SignedInt(-1), &[Token::I64(-1)]);
+        assert_ser_tokens(&MetaValue::Boolean(true), &[Token::Bool(true)]);
+    }
+
+    #[test]
+    fn test_ser_cdtime() {
+        assert_ser_tokens(&CdTime(1_500_000_000), &[Token::F64(1.5)]);
+    }
+
+    #[test]
+    fn test_ser_value_report() {
+        let report = ValueReport {
+            name: "rx",
+            value: Value::Derive(10),
+            min: 0.0,
+            max: 100.0,
+        };
+
+        assert_ser_tokens(
+            &report,
+            &[
+                Token::Struct {
+                    name: "ValueReport",
+                    len: 5,
+                },
+                Token::Str("dsname"),
+                Token::Str("rx"),
+                Token::Str("dstype"),
+                Token::Str("derive"),
+                Token::Str("value"),
+                Token::I64(10),
+                Token::Str("min"),
+                Token::F64(0.0),
+                Token::Str("max"),
+                Token::F64(100.0),
+                Token::StructEnd,
+            ],
+        );
+    }
 }
@@ -0,0 +1,438 @@
+//! Deserialization of the owned value lists from the shape that they are serialized to (and
+//! collectd's write_http JSON), so that recorded values can be replayed.
+use crate::api::{CdTime, MetaValue, Value, ValueListOwned, ValueReportOwned};
+use serde::de::{self, Deserialize, Deserializer, Unexpected, Visitor};
+use serde::Deserialize as DeriveDeserialize;
+use std::collections::HashMap;
+use std::fmt;
+
+struct CdTimeVisitor;
+
+impl Visitor<'_> for CdTimeVisitor {
+    type Value = CdTime;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a non-negative number of seconds")
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<CdTime, E>
+    where
+        E: de::Error,
+    {
+        let ns = v * 1e9;
+        if ns.is_finite() && ns >= 0.0 && ns <= u64::MAX as f64 {
+            Ok(CdTime(ns.round() as u64))
+        } else {
+            Err(E::invalid_value(Unexpected::Float(v), &self))
+        }
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<CdTime, E>
+    where
+        E: de::Error,
+    {
+        v.checked_mul(1_000_000_000)
+            .map(CdTime)
+            .ok_or_else(|| E::invalid_value(Unexpected::Unsigned(v), &self))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<CdTime, E>
+    where
+        E: de::Error,
+    {
+        if v < 0 {
+            Err(E::invalid_value(Unexpected::Signed(v), &self))
+        } else {
+            self.visit_u64(v as u64)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for CdTime {
+    fn deserialize<D>(deserializer: D) -> Result<CdTime, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_f64(CdTimeVisitor)
+    }
+}
+
+struct MetaValueVisitor;
+
+impl Visitor<'_> for MetaValueVisitor {
+    type Value = MetaValue;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a string, number, or boolean")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<MetaValue, E> {
+        Ok(MetaValue::Boolean(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<MetaValue, E> {
+        Ok(MetaValue::SignedInt(v))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<MetaValue, E> {
+        Ok(MetaValue::UnsignedInt(v))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<MetaValue, E> {
+        Ok(MetaValue::Double(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<MetaValue, E> {
+        Ok(MetaValue::String(String::from(v)))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<MetaValue, E> {
+        Ok(MetaValue::String(v))
+    }
+}
+
+/// Deserializes a metadata value from its plain value. Since the kind of metadata is not
+/// serialized, non-negative integers are deserialized as `UnsignedInt` and negative integers as
+/// `SignedInt`.
+impl<'de> Deserialize<'de> for MetaValue {
+    fn deserialize<D>(deserializer: D) -> Result<MetaValue, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(MetaValueVisitor)
+    }
+}
+
+/// A value before its data source type is known
+#[derive(Debug, Clone, Copy)]
+enum Number {
+    Unsigned(u64),
+    Signed(i64),
+    Float(f64),
+}
+
+struct NumberVisitor;
+
+impl Visitor<'_> for NumberVisitor {
+    type Value = Number;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a number or null")
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Number, E> {
+        Ok(Number::Signed(v))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Number, E> {
+        Ok(Number::Unsigned(v))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Number, E> {
+        Ok(Number::Float(v))
+    }
+
+    // JSON can't represent NaN so serializers write a null instead
+    fn visit_unit<E>(self) -> Result<Number, E> {
+        Ok(Number::Float(f64::NAN))
+    }
+
+    fn visit_none<E>(self) -> Result<Number, E> {
+        Ok(Number::Float(f64::NAN))
+    }
+}
+
+impl<'de> Deserialize<'de> for Number {
+    fn deserialize<D>(deserializer: D) -> Result<Number, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(NumberVisitor)
+    }
+}
+
+const DS_TYPES: &[&str] = &["counter", "gauge", "derive", "absolute"];
+
+fn to_value<E: de::Error>(number: Number, ds_type: &str) -> Result<Value, E> {
+    let value = match (ds_type.to_ascii_lowercase().as_str(), number) {
+        ("gauge", Number::Float(x)) => Some(Value::Gauge(x)),
+        ("gauge", Number::Signed(x)) => Some(Value::Gauge(x as f64)),
+        ("gauge", Number::Unsigned(x)) => Some(Value::Gauge(x as f64)),
+        ("counter", Number::Unsigned(x)) => Some(Value::Counter(x)),
+        ("counter", Number::Signed(x)) if x >= 0 => Some(Value::Counter(x as u64)),
+        ("absolute", Number::Unsigned(x)) => Some(Value::Absolute(x)),
+        ("absolute", Number::Signed(x)) if x >= 0 => Some(Value::Absolute(x as u64)),
+        ("derive", Number::Signed(x)) => Some(Value::Derive(x)),
+        ("derive", Number::Unsigned(x)) if x <= i64::MAX as u64 => Some(Value::Derive(x as i64)),
+        ("counter", _) | ("absolute", _) | ("derive", _) => None,
+        _ => return Err(E::unknown_variant(ds_type, DS_TYPES)),
+    };
+
+    value.ok_or_else(|| {
+        let unexpected = match number {
+            Number::Unsigned(x) => Unexpected::Unsigned(x),
+            Number::Signed(x) => Unexpected::Signed(x),
+            Number::Float(x) => Unexpected::Float(x),
+        };
+        E::invalid_value(unexpected, &ds_type)
+    })
+}
+
+fn nan() -> f64 {
+    f64::NAN
+}
+
+#[derive(DeriveDeserialize)]
+#[serde(rename = "ValueReport")]
+struct ReportFields {
+    dsname: String,
+    dstype: String,
+    value: Number,
+    #[serde(default = "nan")]
+    min: f64,
+    #[serde(default = "nan")]
+    max: f64,
+}
+
+impl<'de> Deserialize<'de> for ValueReportOwned {
+    fn deserialize<D>(deserializer: D) -> Result<ValueReportOwned, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let fields = ReportFields::deserialize(deserializer)?;
+        Ok(ValueReportOwned {
+            value: to_value(fields.value, &fields.dstype)?,
+            name: fields.dsname,
+            min: fields.min,
+            max: fields.max,
+        })
+    }
+}
+
+#[derive(DeriveDeserialize)]
+#[serde(rename = "ValueList")]
+struct ListFields {
+    values: Vec<Number>,
+    dstypes: Vec<String>,
+    dsnames: Vec<String>,
+    time: CdTime,
+    interval: CdTime,
+    host: String,
+    plugin: String,
+    #[serde(default)]
+    plugin_instance: String,
+    #[serde(rename = "type")]
+    type_: String,
+    #[serde(default)]
+    type_instance: String,
+    #[serde(default)]
+    meta: HashMap<String, MetaValue>,
+}
+
+/// Deserializes a value list from the write_http JSON format. Since the format doesn't contain
+/// the minimum and maximum of each data source, they are NaN (ie: unbounded). Only the rates of
+/// gauges are known.
+impl<'de> Deserialize<'de> for ValueListOwned {
+    fn deserialize<D>(deserializer: D) -> Result<ValueListOwned, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let fields = ListFields::deserialize(deserializer)?;
+        let len = fields.values.len();
+        if fields.dstypes.len() != len {
+            return Err(de::Error::invalid_length(
+                fields.dstypes.len(),
+                &"as many dstypes as values",
+            ));
+        }
+
+        if fields.dsnames.len() != len {
+            return Err(de::Error::invalid_length(
+                fields.dsnames.len(),
+                &"as many dsnames as values",
+            ));
+        }
+
+        let mut values = Vec::with_capacity(len);
+        let iter = fields.values.into_iter().zip(fields.dstypes.iter());
+        for ((number, ds_type), name) in iter.zip(fields.dsnames) {
+            values.push(ValueReportOwned {
+                name,
+                value: to_value(number, ds_type)?,
+                min: f64::NAN,
+                max: f64::NAN,
+            });
+        }
+
+        let rates = values
+            .iter()
+            .map(|x| match x.value {
+                Value::Gauge(x) => Some(x),
+                _ => None,
+            })
+            .collect();
+
+        let instance = |x: String| Some(x).filter(|x| !x.is_empty());
+        Ok(ValueListOwned {
+            values,
+            rates,
+            plugin: fields.plugin,
+            plugin_instance: instance(fields.plugin_instance),
+            type_: fields.type_,
+            type_instance: instance(fields.type_instance),
+            host: fields.host,
+            time: fields.time.into(),
+            interval: fields.interval.into(),
+            meta: fields.meta,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::prelude::*;
+    use chrono::Duration;
+    use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_ser_tokens, Token};
+
+    /// Compares lists by their debug output, as a NaN minimum or maximum is never equal to itself
+    #[derive(Debug)]
+    struct DebugEq(ValueListOwned);
+
+    impl PartialEq for DebugEq {
+        fn eq(&self, other: &Self) -> bool {
+            format!("{:?}", self.0) == format!("{:?}", other.0)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for DebugEq {
+        fn deserialize<D>(deserializer: D) -> Result<DebugEq, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            ValueListOwned::deserialize(deserializer).map(DebugEq)
+        }
+    }
+
+    fn list_tokens(dstype: &'static str, value: Token) -> Vec<Token> {
+        vec![
+            Token::Struct {
+                name: "ValueList",
+                len: 11,
+            },
+            Token::Str("values"),
+            Token::Seq { len: Some(1) },
+            value,
+            Token::SeqEnd,
+            Token::Str("dstypes"),
+            Token::Seq { len: Some(1) },
+            Token::Str(dstype),
+            Token::SeqEnd,
+            Token::Str("dsnames"),
+            Token::Seq { len: Some(1) },
+            Token::Str("value"),
+            Token::SeqEnd,
+            Token::Str("time"),
+            Token::F64(1.5),
+            Token::Str("interval"),
+            Token::F64(10.0),
+            Token::Str("host"),
+            Token::Str("localhost"),
+            Token::Str("plugin"),
+            Token::Str("interface"),
+            Token::Str("plugin_instance"),
+            Token::Str("eth0"),
+            Token::Str("type"),
+            Token::Str("if_packets"),
+            Token::Str("type_instance"),
+            Token::Str(""),
+            Token::Str("meta"),
+            Token::Map { len: Some(1) },
+            Token::Str("network:received"),
+            Token::Bool(true),
+            Token::MapEnd,
+            Token::StructEnd,
+        ]
+    }
+
+    #[test]
+    fn test_value_list_roundtrip() {
+        let mut meta = HashMap::new();
+        meta.insert(String::from("network:received"), MetaValue::Boolean(true));
+        let list = ValueListOwned {
+            values: vec![ValueReportOwned {
+                name: String::from("value"),
+                value: Value::Derive(-5),
+                min: 0.0,
+                max: 10.0,
+            }],
+            rates: None,
+            plugin: String::from("interface"),
+            plugin_instance: Some(String::from("eth0")),
+            type_: String::from("if_packets"),
+            type_instance: None,
+            host: String::from("localhost"),
+            time: Utc.timestamp_opt(1, 500_000_000).unwrap(),
+            interval: Duration::seconds(10),
+            meta,
+        };
+
+        let tokens = list_tokens("derive", Token::I64(-5));
+        assert_ser_tokens(&list, &tokens);
+
+        let mut expected = list.clone();
+        expected.values[0].min = f64::NAN;
+        expected.values[0].max = f64::NAN;
+
+        assert_de_tokens(&DebugEq(expected), &tokens);
+    }
+
+    #[test]
+    fn test_value_list_gauge_null() {
+        let mut list = ValueListOwned {
+            values: vec![ValueReportOwned {
+                name: String::from("value"),
+                value: Value::Gauge(f64::NAN),
+                min: f64::NAN,
+                max: f64::NAN,
+            }],
+            rates: Some(vec![f64::NAN]),
+            plugin: String::from("interface"),
+            plugin_instance: Some(String::from("eth0")),
+            type_: String::from("if_packets"),
+            type_instance: None,
+            host: String::from("localhost"),
+            time: Utc.timestamp_opt(1, 500_000_000).unwrap(),
+            interval: Duration::seconds(10),
+            meta: HashMap::new(),
+        };
+        list.meta
+            .insert(String::from("network:received"), MetaValue::Boolean(true));
+        assert_de_tokens(&DebugEq(list), &list_tokens("gauge", Token::Unit));
+    }
+
+    #[test]
+    fn test_value_list_invalid_value() {
+        assert_de_tokens_error::<ValueListOwned>(
+            &list_tokens("counter", Token::I64(-5)),
+            "invalid value: integer `-5`, expected counter",
+        );
+        assert_de_tokens_error::<ValueListOwned>(
+            &list_tokens("histogram", Token::U64(5)),
+            "unknown variant `histogram`, expected one of `counter`, `gauge`, `derive`, `absolute`",
+        );
+    }
+
+    #[test]
+    fn test_meta_value() {
+        assert_de_tokens(&MetaValue::UnsignedInt(1), &[Token::U64(1)]);
+        assert_de_tokens(&MetaValue::SignedInt(-1), &[Token::I64(-1)]);
+        assert_de_tokens(&MetaValue::String(String::from("a")), &[Token::Str("a")]);
+    }
+
+    #[test]
+    fn test_cdtime() {
+        assert_de_tokens(&CdTime(1_500_000_000), &[Token::F64(1.5)]);
+        assert_de_tokens(&CdTime(2_000_000_000), &[Token::U64(2)]);
+    }
+}
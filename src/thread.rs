@@ -0,0 +1,113 @@
+//! Spawn threads that inherit collectd's plugin context.
+//!
+//! Collectd stores context (such as the interval of the plugin) in thread local storage, so a
+//! thread spawned with `std::thread::spawn` would see the defaults when it calls
+//! `plugin_get_interval` or submits values. The threads spawned here copy the context of the
+//! spawning thread and are joined when the plugin is shutdown, after `PluginManager::shutdown` has
+//! had the chance to signal them to stop.
+use crate::bindings::{plugin_get_ctx, plugin_set_ctx};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Mutex;
+use std::thread;
+
+static THREADS: Mutex<Vec<thread::JoinHandle<()>>> = Mutex::new(Vec::new());
+
+/// An owned permission to wait on a thread spawned with `spawn`
+#[derive(Debug)]
+pub struct JoinHandle<T> {
+    thread: thread::Thread,
+    result: Receiver<thread::Result<T>>,
+}
+
+impl<T> JoinHandle<T> {
+    /// Extracts a handle to the underlying thread
+    pub fn thread(&self) -> &thread::Thread {
+        &self.thread
+    }
+
+    /// Waits for the thread to finish and returns its result. If the thread panicked, `Err` is
+    /// returned with the parameter given to `panic!`.
+    pub fn join(self) -> thread::Result<T> {
+        self.result
+            .recv()
+            .unwrap_or_else(|_| Err(Box::new("thread exited without a result")))
+    }
+}
+
+/// Spawns a new thread with the plugin context of the current thread and returns a `JoinHandle`
+/// for it. Like `std::thread::spawn`, this will panic if the operating system fails to create the
+/// thread.
+pub fn spawn<F, T>(f: F) -> JoinHandle<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let ctx = unsafe { plugin_get_ctx() };
+    let (tx, rx) = mpsc::channel();
+
+    let mut threads = THREADS.lock().unwrap_or_else(|e| e.into_inner());
+
+    // Prune threads that have run to completion so the list doesn't grow unbounded
+    threads.retain(|x| !x.is_finished());
+
+    let handle = thread::spawn(move || {
+        unsafe {
+            plugin_set_ctx(ctx);
+        }
+
+        let result = panic::catch_unwind(AssertUnwindSafe(f));
+
+        // The receiving end may have already been dropped if no one is interested in the result
+        let _ = tx.send(result);
+    });
+
+    let thread = handle.thread().clone();
+    threads.push(handle);
+    JoinHandle { thread, result: rx }
+}
+
+/// Waits for all threads spawned with `spawn` to finish. Called on plugin shutdown.
+pub(crate) fn join_all() {
+    let threads = {
+        let mut threads = THREADS.lock().unwrap_or_else(|e| e.into_inner());
+        threads.drain(..).collect::<Vec<_>>()
+    };
+
+    for handle in threads {
+        // Panics have been caught and reported through the `JoinHandle`
+        let _ = handle.join();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_spawn_join() {
+        let handle = spawn(|| 1 + 1);
+        assert_eq!(handle.join().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_spawn_panic() {
+        let handle = spawn(|| panic!("oh no"));
+        assert!(handle.join().is_err());
+    }
+
+    #[test]
+    fn test_join_all() {
+        let done = Arc::new(AtomicBool::new(false));
+        let thread_done = done.clone();
+        let _handle = spawn(move || {
+            thread::sleep(std::time::Duration::from_millis(10));
+            thread_done.store(true, Ordering::SeqCst);
+        });
+
+        join_all();
+        assert!(done.load(Ordering::SeqCst));
+    }
+}